@@ -6,6 +6,7 @@ use rayon::prelude::{ParallelIterator, IntoParallelIterator};
 
 use  common::iter_ext::TryIterator;
 use common::input::Linewise;
+use common::profile;
 use  common::pathfinding::{self as pf, Algorithm};
 
 #[derive(Debug, thiserror::Error)]
@@ -163,13 +164,20 @@ fn traverse_masked_map<const MAX: usize>(step: usize, valve: &Valve, mut acc: us
 }
 
 pub fn task1(valves: Linewise<Valve>) -> Result<usize, Error> {
-    let valves = valves.try_collect2()?;
-    let map = Map::from_valves(valves);
+    let valves = {
+        let _span = profile::span("read valves");
+        valves.try_collect2()?
+    };
+    let map = {
+        let _span = profile::span("build map");
+        Map::from_valves(valves)
+    };
 
     let mut visited = HashSet::new();
     let root_name = encode_name("AA");
     let root_node = &map.valves[&root_name];
 
+    let _span = profile::span("traverse");
     let result = traverse_masked_map::<30>(0, &root_node, 0, &map, !0, &mut visited);
 
     Ok(result)
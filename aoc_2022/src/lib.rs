@@ -14,10 +14,10 @@ common::decl_year! {
     #[doc("../docs/day13.md")] day13 {task1;task2;}
     #[doc("../docs/day14.md")] day14 {task1;task2;}
     #[doc("../docs/day15.md")] day15 {task1;task2;}
-    #[doc("../docs/day16.md")] day16 {task1;task2;}
+    #[doc("../docs/day16.md")] day16 [parallel] {task1;task2;}
     #[doc("../docs/day17.md")] day17 {task1;task2;}
     #[doc("../docs/day18.md")] day18 {task1;task2;}
-    #[doc("../docs/day19.md")] day19 {task1;task2;}
+    #[doc("../docs/day19.md")] day19 [parallel] {task1;task2;}
     #[doc("../docs/day20.md")] day20 {task1;task2;}
     #[doc("../docs/day21.md")] day21 {task1;task2;}
     #[doc("../docs/day22.md")] day22 {task1;task2;}
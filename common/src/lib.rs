@@ -1,4 +1,4 @@
-use std::{fmt::Debug, io::BufRead};
+use std::{fmt::Debug, io::BufRead, time::Duration};
 
 pub mod bit_grid;
 pub mod bit_set;
@@ -9,6 +9,7 @@ pub mod iter_ext;
 pub mod macros;
 pub mod num_enum;
 pub mod pathfinding;
+pub mod profile;
 
 #[derive(Debug)]
 pub struct Year {
@@ -26,6 +27,10 @@ impl std::fmt::Display for Year {
 pub struct Day {
     pub name: &'static str,
     pub tasks: &'static [Task],
+    /// Whether the tasks of this day are parallelized internally.
+    pub parallel: bool,
+    /// The puzzle description as markdown if the day is documented.
+    pub doc: Option<&'static str>,
 }
 impl std::fmt::Display for Day {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -33,7 +38,22 @@ impl std::fmt::Display for Day {
     }
 }
 
-type TaskFn = dyn Sync + Fn(&mut dyn BufRead) -> Result<String, String>;
+/// The time spent in the phases of a single task execution.
+///
+/// `parse` covers `Input::parse` only. Inputs which are parsed lazily (i.e. `Linewise`) are
+/// therefore mostly accounted to `solve`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub solve: Duration,
+}
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.parse + self.solve
+    }
+}
+
+type TaskFn = dyn Sync + Fn(&mut dyn BufRead, &mut Timings) -> Result<String, String>;
 pub struct Task {
     pub module: &'static str,
     pub name: &'static str,
@@ -41,7 +61,13 @@ pub struct Task {
 }
 impl Task {
     pub fn run(&self, input: &mut impl BufRead) -> Result<String, String> {
-        (self.func)(input)
+        self.run_timed(input).0
+    }
+
+    pub fn run_timed(&self, input: &mut impl BufRead) -> (Result<String, String>, Timings) {
+        let mut timings = Timings::default();
+        let result = (self.func)(input, &mut timings);
+        (result, timings)
     }
 }
 impl Debug for Task {
@@ -58,12 +84,21 @@ impl std::fmt::Display for Task {
     }
 }
 
+/// Declares the days of a year and the registry of their tasks.
+///
+/// Days whose tasks are parallelized internally are marked with `[parallel]` after their name.
+/// The markdown file given by `#[doc(...)]` is used as the documentation of the day module and is
+/// also available through [`Day::doc`].
 #[macro_export]
 macro_rules! decl_year {
+    (@parallel parallel) => { true };
+    (@parallel) => { false };
+    (@doc $path:literal) => { Some(include_str!($path)) };
+    (@doc) => { None };
     (
         $(
             $(#[doc($path:literal)])?
-            $day:ident {
+            $day:ident $([$flag:ident])? {
                 $(
                     $task:ident;
                 )*
@@ -81,16 +116,30 @@ macro_rules! decl_year {
             days: &[
                 $($crate::Day {
                     name: stringify!($day),
+                    parallel: $crate::decl_year!(@parallel $($flag)?),
+                    doc: $crate::decl_year!(@doc $($path)?),
                     tasks: &[
                         $($crate::Task {
                             module: module_path!(),
                             name: stringify!($task),
-                            func: & |mut read| {
-                                match $crate::input::Input::parse(&mut read) {
-                                    Ok(input) => match $day :: $task (input) {
-                                        Ok(res) => Ok(format!("{}", res)),
-                                        Err(err) => Err(format!("{}", err)),
-                                    },
+                            func: & |mut read, timings| {
+                                let time = std::time::Instant::now();
+                                let span = $crate::profile::span("parse");
+                                let input = $crate::input::Input::parse(&mut read);
+                                drop(span);
+                                timings.parse = time.elapsed();
+                                match input {
+                                    Ok(input) => {
+                                        let time = std::time::Instant::now();
+                                        let span = $crate::profile::span("solve");
+                                        let res = $day :: $task (input);
+                                        drop(span);
+                                        timings.solve = time.elapsed();
+                                        match res {
+                                            Ok(res) => Ok(format!("{}", res)),
+                                            Err(err) => Err(format!("{}", err)),
+                                        }
+                                    }
                                     Err(err) => Err(format!("{}", err)),
                                 }
                             }
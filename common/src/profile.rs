@@ -0,0 +1,162 @@
+//! Lightweight timing of the phases of a task.
+//!
+//! Tasks mark their phases with [`span`] which is timed until the returned guard is dropped.
+//! Spans opened while another span is alive are nested below it and spans with the same name and
+//! parent are merged, so spans in loops do not pile up.
+//! ```ignore
+//! let graph = {
+//!     let _span = common::profile::span("build graph");
+//!     Graph::new(input)
+//! };
+//! ```
+//! Without the `profile` feature spans compile to nothing.
+
+use std::time::Duration;
+
+/// The accumulated time of all spans with the same name and parent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanTiming {
+    pub name: &'static str,
+    /// The number of enclosing spans.
+    pub depth: usize,
+    pub duration: Duration,
+    /// How often the span was entered.
+    pub count: usize,
+}
+
+/// Whether spans are recorded at all.
+pub const ENABLED: bool = cfg!(feature = "profile");
+
+/// Guard of an open span which is closed when dropped.
+#[must_use = "the span is closed when the guard is dropped"]
+pub struct Span {
+    #[cfg(feature = "profile")]
+    inner: Option<(usize, std::time::Instant)>,
+}
+
+/// Opens a span which lasts until the returned guard is dropped.
+#[inline(always)]
+pub fn span(name: &'static str) -> Span {
+    #[cfg(feature = "profile")]
+    {
+        Span {
+            inner: recording::open(name).map(|index| (index, std::time::Instant::now())),
+        }
+    }
+    #[cfg(not(feature = "profile"))]
+    {
+        let _ = name;
+        Span {}
+    }
+}
+
+#[cfg(feature = "profile")]
+impl Drop for Span {
+    fn drop(&mut self) {
+        if let Some((index, start)) = self.inner {
+            recording::close(index, start.elapsed());
+        }
+    }
+}
+
+/// Runs `f` and collects the spans opened by it on the current thread in the order they were
+/// first entered. Without the `profile` feature no spans are collected.
+pub fn collect<T>(f: impl FnOnce() -> T) -> (T, Vec<SpanTiming>) {
+    #[cfg(feature = "profile")]
+    {
+        let previous = recording::start();
+        let result = f();
+        (result, recording::stop(previous))
+    }
+    #[cfg(not(feature = "profile"))]
+    (f(), Vec::new())
+}
+
+#[cfg(feature = "profile")]
+mod recording {
+    use super::SpanTiming;
+    use std::cell::RefCell;
+    use std::time::Duration;
+
+    #[derive(Default)]
+    pub struct Recording {
+        spans: Vec<(Option<usize>, SpanTiming)>,
+        open: Vec<usize>,
+    }
+
+    thread_local! {
+        static CURRENT: RefCell<Option<Recording>> = const { RefCell::new(None) };
+    }
+
+    pub fn start() -> Option<Recording> {
+        CURRENT.replace(Some(Recording::default()))
+    }
+
+    pub fn stop(previous: Option<Recording>) -> Vec<SpanTiming> {
+        let recording = CURRENT.replace(previous).unwrap_or_default();
+        recording.spans.into_iter().map(|(_, s)| s).collect()
+    }
+
+    /// Enters the span and returns its index, if spans are currently collected.
+    pub fn open(name: &'static str) -> Option<usize> {
+        CURRENT.with_borrow_mut(|recording| {
+            let recording = recording.as_mut()?;
+            let parent = recording.open.last().copied();
+            let existing = recording
+                .spans
+                .iter()
+                .position(|(p, s)| *p == parent && s.name == name);
+            let index = existing.unwrap_or_else(|| {
+                let span = SpanTiming {
+                    name,
+                    depth: recording.open.len(),
+                    duration: Duration::ZERO,
+                    count: 0,
+                };
+                recording.spans.push((parent, span));
+                recording.spans.len() - 1
+            });
+            recording.open.push(index);
+            Some(index)
+        })
+    }
+
+    pub fn close(index: usize, elapsed: Duration) {
+        CURRENT.with_borrow_mut(|recording| {
+            let Some(recording) = recording else {
+                return;
+            };
+            // guards dropped out of order also close the spans opened after them
+            if let Some(pos) = recording.open.iter().rposition(|&i| i == index) {
+                recording.open.truncate(pos);
+            }
+            if let Some((_, span)) = recording.spans.get_mut(index) {
+                span.duration += elapsed;
+                span.count += 1;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect() {
+        let (_, spans) = collect(|| {
+            let _outer = span("outer");
+            for _ in 0..3 {
+                let _inner = span("inner");
+            }
+        });
+        let spans = spans
+            .iter()
+            .map(|s| (s.name, s.depth, s.count))
+            .collect::<Vec<_>>();
+        match ENABLED {
+            true => assert_eq!(spans, [("outer", 0, 1), ("inner", 1, 3)]),
+            false => assert_eq!(spans, []),
+        }
+    }
+}
@@ -24,4 +24,7 @@ echo "Created ./%1/src/%2.rs"
 copy NUL ".\%1\inputs\%2.txt"
 echo "Created ./%1/inputs/%2.txt"
 
+if not exist ".\%1\examples\%2" mkdir ".\%1\examples\%2"
+echo "Created ./%1/examples/%2/"
+
 endlocal
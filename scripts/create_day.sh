@@ -21,3 +21,6 @@ echo "Created ./$1/src/$2.rs"
 
 touch "$1/inputs/$2.txt"
 echo "Created ./$1/inputs/$2.txt"
+
+mkdir -p "$1/examples/$2"
+echo "Created ./$1/examples/$2/"
@@ -0,0 +1,166 @@
+use crate::execute::Outcome;
+use common::{Day, Task, Year};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+/// The expected answers of a single year as stored in `YEAR/answers.toml`. The answers for the
+/// inputs of a specific user are stored in `YEAR/answers/USER.toml` instead.
+///
+/// The file contains one table per day keyed by the task name:
+/// ```toml
+/// [day01]
+/// task1 = "24000"
+/// task2 = "45000"
+/// ```
+#[derive(Debug, Default)]
+pub struct Answers {
+    days: HashMap<String, HashMap<String, String>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Failed to read answers file '{0:?}': {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("Failed to parse answers file '{0:?}': {1}")]
+    Parse(PathBuf, toml::de::Error),
+}
+
+/// The outcome of comparing a task result against its expected answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String, got: String },
+    Unknown,
+}
+
+impl Verdict {
+    pub fn is_fail(&self) -> bool {
+        matches!(self, Verdict::Fail { .. })
+    }
+
+    /// The verdict without any details.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Verdict::Pass => "PASS",
+            Verdict::Fail { .. } => "FAIL",
+            Verdict::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Fail { expected, got } => write!(f, "FAIL (expected {expected} got {got})"),
+            _ => f.pad(self.label()),
+        }
+    }
+}
+
+impl Answers {
+    pub fn path(year: &Year, user: Option<&str>) -> PathBuf {
+        match user {
+            Some(user) => PathBuf::from_iter([year.name, "answers", &format!("{user}.toml")]),
+            None => PathBuf::from_iter([year.name, "answers.toml"]),
+        }
+    }
+
+    /// Loads the answers of the given year. A missing file is treated as a year without any
+    /// known answers.
+    pub fn load(year: &Year, user: Option<&str>) -> Result<Self, Error> {
+        let path = Self::path(year, user);
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(Error::Io(path, e)),
+        };
+        match toml::from_str(&text) {
+            Ok(days) => Ok(Self { days }),
+            Err(e) => Err(Error::Parse(path, e)),
+        }
+    }
+
+    pub fn expected(&self, day: &Day, task: &Task) -> Option<&str> {
+        let tasks = self.days.get(day.name)?;
+        tasks.get(task.name).map(String::as_str)
+    }
+
+    pub fn verify(&self, day: &Day, task: &Task, outcome: &Outcome) -> Verdict {
+        match self.expected(day, task) {
+            Some(expected) => compare(expected, outcome),
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+/// Compares the outcome of a task against the expected answer.
+pub fn compare(expected: &str, outcome: &Outcome) -> Verdict {
+    let expected = trim_newlines(expected);
+    let got = match outcome {
+        Outcome::Ok(ok) if trim_newlines(ok).lines().eq(expected.lines()) => {
+            return Verdict::Pass;
+        }
+        Outcome::Ok(ok) => trim_newlines(ok).to_owned(),
+        _ => outcome.message(),
+    };
+    Verdict::Fail {
+        expected: expected.to_owned(),
+        got,
+    }
+}
+
+/// Leading and trailing newlines are ignored so multi-line answers can be stored as toml
+/// multi-line strings. Line endings are compared independently of `\r\n` vs. `\n`.
+fn trim_newlines(s: &str) -> &str {
+    s.trim_matches(['\r', '\n'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static TASK: Task = Task {
+        module: "aoc_2022",
+        name: "task2",
+        func: &|_, _| Ok(String::new()),
+    };
+    static DAY: Day = Day {
+        name: "day10",
+        tasks: &[],
+        parallel: false,
+        doc: None,
+    };
+
+    const ANSWERS: &str = "\
+[day10]
+task2 = '''
+##..
+.##.
+'''
+";
+
+    #[test]
+    fn test_verify() {
+        let answers = Answers {
+            days: toml::from_str(ANSWERS).unwrap(),
+        };
+
+        let res = Outcome::Ok("\r\n##..\r\n.##.".to_owned());
+        assert_eq!(answers.verify(&DAY, &TASK, &res), Verdict::Pass);
+
+        let res = Outcome::Ok("##..".to_owned());
+        let verdict = answers.verify(&DAY, &TASK, &res);
+        assert_eq!(
+            verdict,
+            Verdict::Fail {
+                expected: "##..\n.##.".to_owned(),
+                got: "##..".to_owned()
+            }
+        );
+
+        let empty = Answers::default();
+        assert_eq!(empty.verify(&DAY, &TASK, &res), Verdict::Unknown);
+    }
+}
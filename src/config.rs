@@ -0,0 +1,148 @@
+use crate::report::Format;
+use clap::Command;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+const FILE_NAME: &str = "aoc.toml";
+
+/// Defaults for the command line options read from `aoc.toml`.
+///
+/// The file is looked up in the working directory first and in `$XDG_CONFIG_HOME/aoc` (or
+/// `~/.config/aoc`) second. Options given on the command line or through environment variables
+/// take precedence over the file.
+/// ```toml
+/// inputs_dir = "inputs"
+/// inputs_layout = "{user}/{year}/{day}.txt"
+/// format = "json"
+/// verify = true
+/// threads = 4
+/// timeout = "10s"
+/// command = ["run", "2022", "--output", "my dir/{task}.txt"]
+/// ```
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub inputs_dir: Option<PathBuf>,
+    pub inputs_layout: Option<String>,
+    pub user: Option<String>,
+    pub format: Option<Format>,
+    pub verify: Option<bool>,
+    /// The number of threads used to run tasks in parallel.
+    pub threads: Option<usize>,
+    pub timeout: Option<String>,
+    /// The subcommand including its arguments run if no arguments are given. Each argument is a
+    /// separate string so arguments may contain spaces without any quoting.
+    pub command: Option<Vec<String>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Failed to read config file '{0:?}': {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("Failed to parse config file '{0:?}': {1}")]
+    Parse(PathBuf, toml::de::Error),
+}
+
+impl Config {
+    fn paths() -> Vec<PathBuf> {
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+        let mut paths = vec![PathBuf::from(FILE_NAME)];
+        if let Some(dir) = config_home {
+            paths.push(dir.join("aoc").join(FILE_NAME));
+        }
+        paths
+    }
+
+    /// Loads the first config file found. Without any file all options keep their defaults.
+    pub fn load() -> Result<Self, Error> {
+        for path in Self::paths() {
+            let text = match std::fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(Error::Io(path, e)),
+            };
+            return toml::from_str(&text).map_err(|e| Error::Parse(path, e));
+        }
+        Ok(Self::default())
+    }
+
+    /// The default values of the options by their argument id.
+    fn defaults(&self) -> Vec<(&'static str, String)> {
+        let format = self.format.and_then(|f| {
+            let value = clap::ValueEnum::to_possible_value(&f)?;
+            Some(value.get_name().to_owned())
+        });
+        let values = [
+            (
+                "inputs_dir",
+                self.inputs_dir.as_ref().map(|p| p.display().to_string()),
+            ),
+            ("inputs_layout", self.inputs_layout.clone()),
+            ("user", self.user.clone()),
+            ("format", format),
+            ("verify", self.verify.map(|v| v.to_string())),
+            ("timeout", self.timeout.clone()),
+        ];
+        values
+            .into_iter()
+            .filter_map(|(id, value)| Some((id, value?)))
+            .collect()
+    }
+
+    /// Replaces the defaults of all subcommands accepting one of the configured options.
+    pub fn apply(&self, mut command: Command) -> Command {
+        let defaults = self.defaults();
+        let names = command
+            .get_subcommands()
+            .map(|c| c.get_name().to_owned())
+            .collect::<Vec<_>>();
+        for name in names {
+            command = command.mut_subcommand(name, |mut sub| {
+                for (id, value) in &defaults {
+                    if sub.get_arguments().any(|a| a.get_id() == id) {
+                        sub = sub.mut_arg(id, |a| a.default_value(value.clone()));
+                    }
+                }
+                sub
+            });
+        }
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, FromArgMatches};
+
+    #[test]
+    fn test_apply() {
+        let config: Config = toml::from_str("format = 'json'\nverify = true").unwrap();
+        let command = config.apply(crate::Args::command());
+
+        let parse = |args: &[&str]| {
+            let matches = command.clone().get_matches_from(args);
+            let crate::Args::Run(run) = crate::Args::from_arg_matches(&matches).unwrap() else {
+                panic!("expected the run subcommand");
+            };
+            format!("{run:?}")
+        };
+        let run = parse(&["aoc", "run", "2022", "day01", "task1"]);
+        assert!(run.contains("verify: true") && run.contains("format: Json"));
+        let run = parse(&["aoc", "run", "2022", "day01", "task1", "--verify=false"]);
+        assert!(run.contains("verify: false"));
+        let run = parse(&["aoc", "run", "2022", "day01", "task1", "--format", "csv"]);
+        assert!(run.contains("format: Csv"));
+    }
+
+    #[test]
+    fn test_command() {
+        let config: Config =
+            toml::from_str("command = ['run', '2022', '--input-str', 'a b']").unwrap();
+        let command = config.command.unwrap();
+        assert_eq!(command, ["run", "2022", "--input-str", "a b"]);
+        assert!(toml::from_str::<Config>("command = 'all --ordered'").is_err());
+    }
+}
@@ -0,0 +1,50 @@
+use common::{Day, Task, Year};
+use std::path::{Path, PathBuf};
+
+/// An example input stored as `YEAR/examples/DAY/NAME.txt`.
+///
+/// The expected answer of a task may be stored next to it as `NAME.TASK.expected`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub name: String,
+    pub input: PathBuf,
+}
+
+impl Example {
+    pub fn dir(year: &Year, day: &Day) -> PathBuf {
+        PathBuf::from_iter([year.name, "examples", day.name])
+    }
+
+    /// Lists the examples of the given day sorted by name. A missing directory is treated as a day
+    /// without examples.
+    pub fn list(year: &Year, day: &Day) -> Vec<Example> {
+        Self::list_dir(&Self::dir(year, day))
+    }
+
+    /// Lists all `*.txt` files of the directory sorted by name.
+    pub fn list_dir(dir: &Path) -> Vec<Example> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut examples = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "txt"))
+            .filter_map(|input| {
+                let name = input.file_stem()?.to_str()?.to_owned();
+                Some(Example { name, input })
+            })
+            .collect::<Vec<_>>();
+        examples.sort_by(|a, b| a.name.cmp(&b.name));
+        examples
+    }
+
+    pub fn expected_path(&self, task: &Task) -> PathBuf {
+        self.input
+            .with_file_name(format!("{}.{}.expected", self.name, task.name))
+    }
+
+    /// The expected answer of the task for this example if there is a sidecar file.
+    pub fn expected(&self, task: &Task) -> Option<String> {
+        std::fs::read_to_string(self.expected_path(task)).ok()
+    }
+}
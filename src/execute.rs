@@ -0,0 +1,376 @@
+use crate::memory::Memory;
+use common::profile::SpanTiming;
+use common::{Task, Timings};
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::fmt::Display;
+use std::io::Cursor;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{RecvTimeoutError, channel};
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant};
+
+/// The result of executing a single task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Ok(String),
+    Err(String),
+    Panic { message: String, location: String },
+    Timeout(Duration),
+}
+
+impl Outcome {
+    pub fn status(&self) -> &'static str {
+        match self {
+            Outcome::Ok(_) => "OK",
+            Outcome::Err(_) => "ERR",
+            Outcome::Panic { .. } => "PANIC",
+            Outcome::Timeout(_) => "TIMEOUT",
+        }
+    }
+
+    /// The answer of the task or a description of why it failed.
+    pub fn message(&self) -> String {
+        match self {
+            Outcome::Ok(ok) => ok.clone(),
+            Outcome::Err(e) => e.clone(),
+            Outcome::Panic { message, location } => format!("panicked at {location}: {message}"),
+            Outcome::Timeout(timeout) => {
+                let timeout = crate::format_duration(*timeout);
+                format!("exceeded {timeout}, still running in the background")
+            }
+        }
+    }
+}
+
+impl From<Result<String, String>> for Outcome {
+    fn from(value: Result<String, String>) -> Self {
+        match value {
+            Ok(ok) => Outcome::Ok(ok),
+            Err(e) => Outcome::Err(e),
+        }
+    }
+}
+
+/// Everything measured while executing a single task.
+#[derive(Debug, Clone)]
+pub struct Execution {
+    pub outcome: Outcome,
+    pub timings: Timings,
+    /// The heap usage, only measured with the `memory` feature.
+    pub memory: Option<Memory>,
+    /// The spans opened by the task, only collected with the `profile` feature.
+    pub spans: Vec<SpanTiming>,
+}
+
+impl From<Outcome> for Execution {
+    /// An execution which did not get to run the task at all.
+    fn from(outcome: Outcome) -> Self {
+        Self {
+            outcome,
+            timings: Timings::default(),
+            memory: None,
+            spans: Vec::new(),
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message())
+    }
+}
+
+thread_local! {
+    static IN_TASK: Cell<bool> = const { Cell::new(false) };
+    static LAST_PANIC: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+}
+
+/// The number of tasks currently executed on any thread.
+static RUNNING: AtomicUsize = AtomicUsize::new(0);
+/// The message and location of recent panics on rayon worker threads. A task only gets the
+/// payload of such a panic back from rayon, so its location is looked up here by the message.
+static WORKER_PANICS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+const MAX_WORKER_PANICS: usize = 64;
+
+fn is_worker_thread() -> bool {
+    #[cfg(feature = "parallel")]
+    return rayon::current_thread_index().is_some();
+    #[cfg(not(feature = "parallel"))]
+    false
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(s), _) => s.to_string(),
+        (_, Some(s)) => s.clone(),
+        _ => "Box<dyn Any>".to_owned(),
+    }
+}
+
+/// Installs a panic hook which records panics of tasks instead of printing them, including the
+/// panics of the worker threads tasks hand their work to. Other panics are passed on to the
+/// previous hook.
+fn install_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let in_task = IN_TASK.get();
+            let in_worker = is_worker_thread() && RUNNING.load(Ordering::SeqCst) > 0;
+            if !in_task && !in_worker {
+                return previous(info);
+            }
+            let message = payload_message(info.payload());
+            let location = match info.location() {
+                Some(l) => format!("{}:{}:{}", l.file(), l.line(), l.column()),
+                None => "<unknown>".to_owned(),
+            };
+            if in_worker {
+                let mut panics = WORKER_PANICS.lock().unwrap_or_else(|e| e.into_inner());
+                if panics.len() >= MAX_WORKER_PANICS {
+                    panics.remove(0);
+                }
+                panics.push((message.clone(), location.clone()));
+            }
+            if in_task {
+                LAST_PANIC.set(Some((message, location)));
+            }
+        }));
+    });
+}
+
+/// The location of the panic with the given message, recorded by the hook either on the task's
+/// own thread or on a worker thread.
+fn panic_location(message: &str) -> String {
+    if let Some((last, location)) = LAST_PANIC.take()
+        && last == message
+    {
+        return location;
+    }
+    let mut panics = WORKER_PANICS.lock().unwrap_or_else(|e| e.into_inner());
+    match panics.iter().rposition(|(m, _)| m == message) {
+        Some(i) => panics.remove(i).1,
+        None => "<unknown>".to_owned(),
+    }
+}
+
+fn run_isolated(task: &Task, input: &[u8]) -> Execution {
+    install_hook();
+    RUNNING.fetch_add(1, Ordering::SeqCst);
+    // a rayon worker may run another task while the task it runs is blocked
+    let in_task = IN_TASK.replace(true);
+    let time = Instant::now();
+    let ((result, spans), memory) = crate::memory::measure(|| {
+        common::profile::collect(|| {
+            std::panic::catch_unwind(AssertUnwindSafe(|| task.run_timed(&mut Cursor::new(input))))
+        })
+    });
+    IN_TASK.set(in_task);
+    RUNNING.fetch_sub(1, Ordering::SeqCst);
+
+    let (outcome, timings) = match result {
+        Ok((result, timings)) => (result.into(), timings),
+        Err(payload) => {
+            // panics of worker threads are re-raised by rayon with the original payload
+            let message = payload_message(&*payload);
+            let location = panic_location(&message);
+            let timings = Timings {
+                parse: Duration::ZERO,
+                solve: time.elapsed(),
+            };
+            (Outcome::Panic { message, location }, timings)
+        }
+    };
+    Execution {
+        outcome,
+        timings,
+        memory,
+        spans,
+    }
+}
+
+/// Runs the task on the given input catching any panic.
+///
+/// With a timeout the task is executed on a separate thread. Should it not finish in time, the
+/// thread is abandoned and keeps running in the background until it is done or the process exits.
+/// Until then the memory of all other tasks is marked as incomplete and panics of any rayon worker
+/// are attributed to tasks instead of being printed.
+pub fn run(task: &'static Task, input: Vec<u8>, timeout: Option<Duration>) -> Execution {
+    run_with(task, input, timeout, |f| f())
+}
+
+/// Like [`run`] but the parallel iterators of the task run on `pool`, even if the task itself is
+/// moved to a separate thread for the timeout.
+#[cfg(feature = "parallel")]
+pub fn run_in(
+    pool: &std::sync::Arc<rayon::ThreadPool>,
+    task: &'static Task,
+    input: Vec<u8>,
+    timeout: Option<Duration>,
+) -> Execution {
+    let pool = pool.clone();
+    run_with(task, input, timeout, move |f| pool.install(f))
+}
+
+/// Executes the task through `install` on the thread which runs it.
+fn run_with(
+    task: &'static Task,
+    input: Vec<u8>,
+    timeout: Option<Duration>,
+    install: impl FnOnce(Box<dyn FnOnce() -> Execution + Send>) -> Execution + Send + 'static,
+) -> Execution {
+    let Some(timeout) = timeout else {
+        return install(Box::new(move || run_isolated(task, &input)));
+    };
+
+    let (send, recv) = channel();
+    let spawned = std::thread::Builder::new()
+        .name(format!("{}::{}", task.module, task.name))
+        .spawn(move || {
+            let _ = send.send(install(Box::new(move || run_isolated(task, &input))));
+        });
+    if let Err(e) = spawned {
+        return Outcome::Err(e.to_string()).into();
+    }
+
+    match recv.recv_timeout(timeout) {
+        Ok(res) => res,
+        Err(RecvTimeoutError::Timeout) => {
+            let timings = Timings {
+                parse: Duration::ZERO,
+                solve: timeout,
+            };
+            Execution {
+                timings,
+                ..Outcome::Timeout(timeout).into()
+            }
+        }
+        Err(RecvTimeoutError::Disconnected) => {
+            unreachable!("the task thread always sends a result")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static PANICS: Task = Task {
+        module: "tests",
+        name: "panics",
+        func: &|_, _| panic!("oh no"),
+    };
+    static STOP: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+    static LOOPS: Task = Task {
+        module: "tests",
+        name: "loops",
+        func: &|_, _| {
+            while !STOP.load(Ordering::SeqCst) {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            Ok(String::new())
+        },
+    };
+
+    fn exclusive() -> std::sync::MutexGuard<'static, ()> {
+        crate::memory::EXCLUSIVE
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[test]
+    fn test_panic() {
+        let _exclusive = exclusive();
+        let outcome = run(&PANICS, Vec::new(), None).outcome;
+        let Outcome::Panic { message, location } = outcome else {
+            panic!("expected a panic, got {outcome:?}");
+        };
+        assert_eq!(message, "oh no");
+        assert!(location.starts_with(file!()));
+    }
+
+    #[test]
+    fn test_panic_after_nested() {
+        let _exclusive = exclusive();
+        static NESTS: Task = Task {
+            module: "tests",
+            name: "nests",
+            func: &|_, _| {
+                // like a task a rayon worker runs while the enclosing one is blocked
+                let _ = run(&PANICS, Vec::new(), None);
+                panic!("oh no, again")
+            },
+        };
+
+        let outcome = run(&NESTS, Vec::new(), None).outcome;
+        let Outcome::Panic { message, location } = outcome else {
+            panic!("expected a panic, got {outcome:?}");
+        };
+        assert_eq!(message, "oh no, again");
+        assert!(location.starts_with(file!()));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_panic_in_worker() {
+        use rayon::prelude::*;
+        let _exclusive = exclusive();
+        static PANICS_IN_WORKER: Task = Task {
+            module: "tests",
+            name: "panics_in_worker",
+            func: &|_, _| {
+                (0..4)
+                    .into_par_iter()
+                    .for_each(|i| assert!(i < 3, "oh no {i}"));
+                Ok(String::new())
+            },
+        };
+
+        let outcome = run(&PANICS_IN_WORKER, Vec::new(), None).outcome;
+        let Outcome::Panic { message, location } = outcome else {
+            panic!("expected a panic, got {outcome:?}");
+        };
+        assert_eq!(message, "oh no 3");
+        assert!(location.starts_with(file!()));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_run_in() {
+        let _exclusive = exclusive();
+        static THREADS: Task = Task {
+            module: "tests",
+            name: "threads",
+            func: &|_, _| Ok(rayon::current_num_threads().to_string()),
+        };
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(3)
+            .build()
+            .unwrap();
+        let pool = std::sync::Arc::new(pool);
+        let timeout = Some(Duration::from_secs(10));
+        let outcome = run_in(&pool, &THREADS, Vec::new(), timeout).outcome;
+        assert_eq!(outcome, Outcome::Ok("3".to_owned()));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let _exclusive = exclusive();
+        let timeout = Duration::from_millis(10);
+        let outcome = run(&LOOPS, Vec::new(), Some(timeout)).outcome;
+        assert_eq!(outcome, Outcome::Timeout(timeout));
+
+        // the abandoned task would make the measurements of the following tests incomplete
+        assert_eq!(RUNNING.load(Ordering::SeqCst), 1);
+        STOP.store(true, Ordering::SeqCst);
+        while RUNNING.load(Ordering::SeqCst) > 0 {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
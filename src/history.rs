@@ -0,0 +1,191 @@
+use crate::report::Record;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// A single task execution as stored in the history.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Entry {
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    pub year: String,
+    pub day: String,
+    pub task: String,
+    pub user: Option<String>,
+    /// The [`hash`] of the input formatted as hex.
+    pub input_hash: String,
+    pub status: String,
+    pub answer: String,
+    pub duration_ns: u64,
+}
+
+impl Entry {
+    /// Creates the entry of a record whose input had the given [`hash`].
+    pub fn new(record: &Record, input_hash: u64) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Self {
+            timestamp,
+            year: record.year.name.to_owned(),
+            day: record.day.name.to_owned(),
+            task: record.task.name.to_owned(),
+            user: record.user.clone(),
+            input_hash: format!("{input_hash:016x}"),
+            status: record.outcome.status().to_owned(),
+            answer: record.outcome.message(),
+            duration_ns: record.duration.as_nanos() as u64,
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_nanos(self.duration_ns)
+    }
+
+    /// Entries with the same key are comparable as they ran the same task on the same input.
+    pub fn key(&self) -> (&str, &str, &str, &str) {
+        (&self.year, &self.day, &self.task, &self.input_hash)
+    }
+
+    fn is_ok(&self) -> bool {
+        self.status == "OK"
+    }
+}
+
+/// The differences of an entry to the previous entry with the same key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Answer {
+        previous: String,
+    },
+    /// The duration increased by the given percentage.
+    Regression {
+        percent: u64,
+    },
+}
+
+/// Compares two runs of the same task on the same input. Timings are only compared for
+/// successful runs as failures usually end early.
+pub fn compare(previous: &Entry, current: &Entry, threshold: f64) -> Vec<Change> {
+    let mut changes = Vec::new();
+    if previous.answer != current.answer || previous.status != current.status {
+        changes.push(Change::Answer {
+            previous: previous.answer.clone(),
+        });
+    }
+    if previous.is_ok() && current.is_ok() && previous.duration_ns > 0 {
+        let ratio = current.duration_ns as f64 / previous.duration_ns as f64;
+        if ratio > 1.0 + threshold / 100.0 {
+            let percent = ((ratio - 1.0) * 100.0).round() as u64;
+            changes.push(Change::Regression { percent });
+        }
+    }
+    changes
+}
+
+/// Hashes the input with 64 bit FNV-1a which, unlike the std hashers, is stable across builds.
+pub fn hash(input: &[u8]) -> u64 {
+    input.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Failed to access history file '{0:?}': {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("Failed to parse line {1} of history file '{0:?}': {2}")]
+    Parse(PathBuf, usize, serde_json::Error),
+}
+
+/// The results of all past runs stored as JSON lines in `.aoc/history`.
+pub struct History;
+
+impl History {
+    pub fn path() -> &'static Path {
+        Path::new(".aoc/history")
+    }
+
+    pub fn append(entries: &[Entry]) -> Result<(), Error> {
+        let path = Self::path();
+        let io = |e| Error::Io(path.to_owned(), e);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(io)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(io)?;
+        let mut lines = Vec::new();
+        for entry in entries {
+            serde_json::to_writer(&mut lines, entry).expect("entries are always serializable");
+            lines.push(b'\n');
+        }
+        // written at once so concurrent runs do not interleave their lines
+        file.write_all(&lines).map_err(io)
+    }
+
+    /// Loads all entries in the order they were recorded. A missing file is an empty history.
+    pub fn load() -> Result<Vec<Entry>, Error> {
+        let path = Self::path();
+        let file = match std::fs::File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Error::Io(path.to_owned(), e)),
+        };
+        let mut entries = Vec::new();
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| Error::Io(path.to_owned(), e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry =
+                serde_json::from_str(&line).map_err(|e| Error::Parse(path.to_owned(), i + 1, e))?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(answer: &str, duration_ns: u64) -> Entry {
+        Entry {
+            timestamp: 0,
+            year: "aoc_2022".to_owned(),
+            day: "day01".to_owned(),
+            task: "task1".to_owned(),
+            user: None,
+            input_hash: format!("{:016x}", hash(b"1\n2\n")),
+            status: "OK".to_owned(),
+            answer: answer.to_owned(),
+            duration_ns,
+        }
+    }
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(hash(b""), 0xcbf29ce484222325);
+        assert_eq!(hash(b"a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_compare() {
+        let previous = entry("42", 100);
+        assert_eq!(compare(&previous, &entry("42", 110), 20.0), vec![]);
+        assert_eq!(
+            compare(&previous, &entry("43", 150), 20.0),
+            vec![
+                Change::Answer {
+                    previous: "42".to_owned()
+                },
+                Change::Regression { percent: 50 }
+            ]
+        );
+    }
+}
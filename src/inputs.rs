@@ -0,0 +1,198 @@
+use common::{Day, Year};
+use std::path::{Path, PathBuf};
+
+/// The layout of the input files used unless configured otherwise.
+pub const DEFAULT_LAYOUT: &str = "{year}/inputs/{day}.txt";
+
+/// Command line options selecting where the inputs are read from.
+#[derive(Debug, Clone, clap_derive::Args)]
+pub struct InputArgs {
+    #[clap(
+        long,
+        env = "AOC_INPUTS_DIR",
+        help = "The directory the inputs layout is relative to. Defaults to the working directory."
+    )]
+    inputs_dir: Option<PathBuf>,
+    #[clap(
+        long,
+        env = "AOC_INPUTS_LAYOUT",
+        help = "The path of an input within the inputs directory. May contain the placeholders '{user}', '{year}' and '{day}' (i.e. '{user}/{year}/{day}.txt'). Defaults to '{year}/inputs/{day}.txt'."
+    )]
+    inputs_layout: Option<String>,
+    #[clap(
+        long,
+        env = "AOC_USER",
+        help = "The user whose inputs are used if the layout contains '{user}'."
+    )]
+    user: Option<String>,
+}
+
+impl InputArgs {
+    pub fn inputs(&self) -> Inputs {
+        Inputs {
+            dir: self.inputs_dir.clone().unwrap_or_default(),
+            layout: match &self.inputs_layout {
+                Some(layout) => layout.clone(),
+                None => DEFAULT_LAYOUT.to_owned(),
+            },
+        }
+    }
+
+    pub fn user(&self) -> Option<&str> {
+        self.user.as_deref()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Failed to list the users in '{0:?}': {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("Found inputs of several users ({0}), select one with '--user'")]
+    AmbiguousUser(String),
+    #[error("Found no users in '{0:?}'")]
+    NoUsers(PathBuf),
+    #[error("The inputs layout '{0}' contains '{{user}}', select a user with '--user'")]
+    MissingUser(String),
+}
+
+/// Resolves the path of the input of a day.
+///
+/// The path is given by a layout relative to a directory. If the layout contains a `{user}`
+/// component, every sub directory at its position holds the inputs of one user.
+#[derive(Debug, Clone)]
+pub struct Inputs {
+    dir: PathBuf,
+    layout: String,
+}
+
+impl Default for Inputs {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::new(),
+            layout: DEFAULT_LAYOUT.to_owned(),
+        }
+    }
+}
+
+impl Inputs {
+    pub fn has_users(&self) -> bool {
+        self.layout.contains("{user}")
+    }
+
+    /// The directory containing the sub directories of all users.
+    fn users_dir(&self) -> PathBuf {
+        let prefix = self.layout.split("{user}").next().unwrap_or_default();
+        self.dir.join(prefix)
+    }
+
+    /// Lists all users sorted by name. Empty if the layout does not distinguish users, but a
+    /// layout with users has to have at least one.
+    pub fn users(&self) -> Result<Vec<String>, Error> {
+        if !self.has_users() {
+            return Ok(Vec::new());
+        }
+        let dir = self.users_dir();
+        let entries = std::fs::read_dir(&dir).map_err(|e| Error::Io(dir.clone(), e))?;
+        let mut users = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .filter_map(|e| e.file_name().to_str().map(str::to_owned))
+            .collect::<Vec<_>>();
+        if users.is_empty() {
+            return Err(Error::NoUsers(dir));
+        }
+        users.sort();
+        Ok(users)
+    }
+
+    /// Picks the user whose inputs should be used. Without an explicit choice this is only
+    /// possible if the inputs of exactly one user exist.
+    pub fn user(&self, user: Option<&str>) -> Result<Option<String>, Error> {
+        if !self.has_users() {
+            return Ok(None);
+        }
+        if let Some(user) = user {
+            return Ok(Some(user.to_owned()));
+        }
+        let mut users = self.users()?;
+        match users.len() {
+            1 => Ok(users.pop()),
+            _ => Err(Error::AmbiguousUser(users.join(", "))),
+        }
+    }
+
+    /// The path of the input of the day, which requires a user if the layout distinguishes them.
+    pub fn path(&self, user: Option<&str>, year: &Year, day: &Day) -> Result<PathBuf, Error> {
+        let mut path = self
+            .layout
+            .replace("{year}", year.name)
+            .replace("{day}", day.name);
+        match user {
+            Some(user) => path = path.replace("{user}", user),
+            None if self.has_users() => return Err(Error::MissingUser(self.layout.clone())),
+            None => {}
+        }
+        Ok(self.dir.join(path))
+    }
+
+    /// The directory the input of the day is located in. Without a user the directory
+    /// containing all users is returned instead.
+    pub fn dir(&self, user: Option<&str>, year: &Year, day: &Day) -> PathBuf {
+        let Ok(path) = self.path(user, year, day) else {
+            return self.users_dir();
+        };
+        match path.parent() {
+            Some(parent) => parent.to_owned(),
+            None => Path::new(".").to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static DAY: Day = Day {
+        name: "day06",
+        tasks: &[],
+        parallel: false,
+        doc: None,
+    };
+    static YEAR: Year = Year {
+        name: "aoc_2024",
+        days: &[],
+    };
+
+    #[test]
+    fn test_path() {
+        let default = Inputs::default();
+        let path = default.path(None, &YEAR, &DAY).unwrap();
+        assert_eq!(path, Path::new("aoc_2024/inputs/day06.txt"));
+        assert_eq!(default.dir(None, &YEAR, &DAY), Path::new("aoc_2024/inputs"));
+
+        let users = Inputs {
+            dir: PathBuf::from("inputs"),
+            layout: "{user}/{year}/{day}.txt".to_owned(),
+        };
+        let path = users.path(Some("alice"), &YEAR, &DAY).unwrap();
+        assert_eq!(path, Path::new("inputs/alice/aoc_2024/day06.txt"));
+        let err = users.path(None, &YEAR, &DAY).unwrap_err();
+        assert!(matches!(err, Error::MissingUser(_)));
+        assert_eq!(users.dir(None, &YEAR, &DAY), Path::new("inputs"));
+        assert_eq!(users.user(Some("bob")).unwrap().as_deref(), Some("bob"));
+        assert_eq!(default.user(Some("bob")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_no_users() {
+        let dir = std::env::temp_dir().join(format!("aoc_no_users_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let inputs = Inputs {
+            dir: dir.clone(),
+            layout: "{user}/{year}/{day}.txt".to_owned(),
+        };
+        assert!(matches!(inputs.users(), Err(Error::NoUsers(_))));
+        assert!(matches!(inputs.user(None), Err(Error::NoUsers(_))));
+        std::fs::remove_dir(dir).unwrap();
+    }
+}
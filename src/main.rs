@@ -2,17 +2,37 @@ pub use aoc_2021;
 pub use aoc_2022;
 pub use aoc_2023;
 
+use answers::Verdict;
+use common::profile::SpanTiming;
 use common::*;
+use execute::Outcome;
 use std::time::Duration;
 
+mod answers;
+mod config;
+mod examples;
+mod execute;
+mod history;
+mod inputs;
+mod memory;
+mod progress;
+mod report;
 mod runner {
     #[cfg(feature = "parallel")]
     pub mod all;
+    pub mod bench;
     pub mod cli;
+    pub mod completions;
+    pub mod examples;
+    pub mod history;
     pub mod run;
     #[cfg(feature = "interactive")]
     pub mod tui;
+    pub mod watch;
 }
+mod select;
+mod stats;
+mod summary;
 
 static YEARS: &[&common::Year] = &[
     &aoc_2021::YEAR,
@@ -29,20 +49,68 @@ pub enum Args {
     All(runner::all::Args),
     #[command(about = "Runs the specified task and returns.")]
     Run(runner::run::Args),
+    #[command(about = "Runs the specified tasks repeatedly and reports timing statistics.")]
+    Bench(runner::bench::Args),
+    #[command(about = "Runs the specified tasks on all example inputs of their day.")]
+    Examples(runner::examples::Args),
+    #[command(about = "Re-runs the specified task whenever its input changes.")]
+    Watch(runner::watch::Args),
     #[cfg(feature = "interactive")]
     #[command(about = "Renders a terminal user interface for interactive execution of tasks.")]
     Tui(runner::tui::Args),
+    #[command(about = "Lists past runs highlighting changed answers and timing regressions.")]
+    History(runner::history::Args),
+    #[command(about = "Prints a completion script for the given shell to stdout.")]
+    Completions(runner::completions::Args),
 }
 
+#[derive(Debug, thiserror::Error)]
+#[error("{0} task(s) did not match their expected answer")]
+pub struct VerificationFailed(usize);
+
 fn main() -> Result<(), anyhow::Error> {
-    if std::env::args().count() > 1 {
-        let args = <Args as clap::Parser>::parse();
+    // a broken config only fails the commands it applies to, so `--help`, `history` and
+    // `completions` keep working
+    let (config, config_error) = match config::Config::load() {
+        Ok(config) => (config, None),
+        Err(e) => (config::Config::default(), Some(e)),
+    };
+    #[cfg(feature = "parallel")]
+    if let Some(threads) = config.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()?;
+    }
+
+    let mut args = std::env::args_os().collect::<Vec<_>>();
+    if let (1, Some(command)) = (args.len(), &config.command) {
+        args.extend(command.iter().map(Into::into));
+    }
+
+    if args.len() > 1 {
+        let command = config.apply(<Args as clap::CommandFactory>::command());
+        let matches = command.get_matches_from(args);
+        let args =
+            <Args as clap::FromArgMatches>::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        let uses_config = !matches!(args, Args::History(_) | Args::Completions(_));
+        if let Some(e) = config_error.filter(|_| uses_config) {
+            return Err(e.into());
+        }
         match args {
             Args::All(args) => runner::all::run(args)?,
             Args::Run(args) => runner::run::run(args)?,
+            Args::Bench(args) => runner::bench::run(args)?,
+            Args::Examples(args) => runner::examples::run(args)?,
+            Args::Watch(args) => runner::watch::run(args)?,
             Args::Tui(args) => runner::tui::run(args)?,
+            Args::History(args) => runner::history::run(args)?,
+            Args::Completions(args) => runner::completions::run(args)?,
         };
     } else {
+        // the command of the config would have been run instead
+        if let Some(e) = config_error {
+            return Err(e.into());
+        }
         runner::cli::run()?;
     }
     Ok(())
@@ -73,22 +141,101 @@ fn format_duration(duration: Duration) -> String {
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid duration '{0}', expected a number followed by 'ns', 'us', 'ms', 's' or 'm'")]
+pub struct InvalidDuration(String);
+
+/// Parses durations such as `500ms`, `1.5s` or `2m`.
+fn parse_duration(s: &str) -> Result<Duration, InvalidDuration> {
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let value = value
+        .parse::<f64>()
+        .map_err(|_| InvalidDuration(s.to_owned()))?;
+    let secs = match unit.trim() {
+        "ns" => value / 1e9,
+        "us" | "ys" => value / 1e6,
+        "ms" => value / 1e3,
+        "s" | "" => value,
+        "m" => value * 60.0,
+        _ => return Err(InvalidDuration(s.to_owned())),
+    };
+    Ok(Duration::from_secs_f64(secs))
+}
+
 fn format_detailed(
-    res: Result<String, String>,
+    outcome: &Outcome,
     y: &Year,
     d: &Day,
     t: &Task,
     duration: Duration,
+    memory: Option<memory::Memory>,
+    verdict: &Verdict,
 ) -> String {
-    let (status, message) = match res {
-        Ok(ok) => ("OK ", ok),
-        Err(e) => ("ERR", e),
-    };
+    let status = outcome.status();
+    let message = outcome.message();
 
     let duration = format_duration(duration);
+    let memory = memory.map(|m| format!(" [{m}]")).unwrap_or_default();
     let year = y.name;
     let day = d.name;
     let task = t.name;
 
-    format!("{status} [{duration:9}] {year:8}::{day:0>5}::{task:5} {message}")
+    // a failed verdict already contains the result
+    match verdict {
+        Verdict::Fail { .. } => {
+            format!("{status:3} [{duration:9}]{memory} {year:8}::{day:0>5}::{task:5} {verdict}")
+        }
+        _ => {
+            format!(
+                "{status:3} [{duration:9}]{memory} {year:8}::{day:0>5}::{task:5} {verdict:7} {message}"
+            )
+        }
+    }
+}
+
+/// Formats the spans of a task as an indented tree with their share of the total time. Without
+/// any spans only the parse and solve phases are listed.
+fn format_profile(spans: &[SpanTiming], timings: Timings) -> String {
+    let phases =
+        [("parse", timings.parse), ("solve", timings.solve)].map(|(name, duration)| SpanTiming {
+            name,
+            depth: 0,
+            duration,
+            count: 1,
+        });
+    let spans = match spans.is_empty() {
+        true => &phases[..],
+        false => spans,
+    };
+    let total = timings.total().as_secs_f64();
+
+    let mut lines = Vec::new();
+    for span in spans {
+        let duration = format_duration(span.duration);
+        let percent = match total > 0.0 {
+            true => span.duration.as_secs_f64() / total * 100.0,
+            false => 0.0,
+        };
+        let indent = "  ".repeat(span.depth);
+        let count = match span.count {
+            1 => String::new(),
+            n => format!(" (x{n})"),
+        };
+        lines.push(format!(
+            "  {duration:>10} {percent:5.1}%  {indent}{}{count}",
+            span.name
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_args() {
+        <super::Args as clap::CommandFactory>::command().debug_assert();
+    }
 }
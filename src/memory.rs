@@ -0,0 +1,239 @@
+use std::fmt::Display;
+
+/// The heap usage of a single task execution.
+///
+/// Allocations of other threads (i.e. the rayon workers a task hands its work to) are included as
+/// long as no other task is measured at the same time. Otherwise they cannot be told apart from
+/// the allocations of the other task and only the thread running the task is counted. A task run
+/// by the same thread while the measured one is blocked (i.e. stolen by a rayon worker) is counted
+/// as well.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Memory {
+    /// The highest number of bytes allocated at once on top of what was allocated before. The
+    /// peaks of the task's thread and of the other threads are added up.
+    pub peak: usize,
+    pub allocations: usize,
+    /// Whether exactly the allocations made for the task are included. Otherwise the numbers are
+    /// marked with a `~` when displayed.
+    pub complete: bool,
+}
+
+impl Display for Memory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let peak = format_bytes(self.peak);
+        let about = if self.complete { "" } else { "~" };
+        write!(f, "{about}{peak} peak, {about}{} allocs", self.allocations)
+    }
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes}B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1}{}", UNITS[unit])
+}
+
+/// Runs `f` and measures its heap usage. Without the `memory` feature nothing is measured.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<Memory>) {
+    #[cfg(feature = "memory")]
+    {
+        use counting::*;
+        use std::sync::atomic::Ordering::SeqCst;
+
+        // other measurements starting or running in the meantime share the other threads
+        let generation = GENERATION.fetch_add(1, SeqCst) + 1;
+        let alone = ACTIVE.fetch_add(1, SeqCst) == 0;
+        let (start, count) = (CURRENT.get(), COUNT.get());
+        let (others_start, others_count) = (OTHERS_CURRENT.load(SeqCst), OTHERS_COUNT.load(SeqCst));
+        if alone {
+            OTHERS_PEAK.store(others_start, SeqCst);
+        }
+        // a rayon worker may run another task while the task it runs is blocked, so the state of
+        // an enclosing measurement on this thread is restored afterwards
+        let previous = (
+            PEAK.replace(start),
+            MEASURING.replace(true),
+            NESTED.replace(false),
+        );
+        let result = f();
+        let peak = PEAK.get();
+        let nested = NESTED.get();
+        PEAK.set(previous.0.max(peak));
+        MEASURING.set(previous.1);
+        NESTED.set(previous.2 || previous.1);
+        ACTIVE.fetch_sub(1, SeqCst);
+        let alone = alone && GENERATION.load(SeqCst) == generation;
+
+        // the allocations of a nested measurement are included in the ones of this thread
+        let own = Memory {
+            peak: (peak - start).max(0) as usize,
+            allocations: COUNT.get() - count,
+            complete: !nested,
+        };
+        let others_allocations = OTHERS_COUNT.load(SeqCst) - others_count;
+        let memory = match alone {
+            true => Memory {
+                peak: own.peak + (OTHERS_PEAK.load(SeqCst) - others_start).max(0) as usize,
+                allocations: own.allocations + others_allocations,
+                ..own
+            },
+            false => Memory {
+                complete: own.complete && others_allocations == 0,
+                ..own
+            },
+        };
+        (result, Some(memory))
+    }
+    #[cfg(not(feature = "memory"))]
+    (f(), None)
+}
+
+#[cfg(feature = "memory")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+    use std::sync::atomic::{AtomicIsize, AtomicUsize, Ordering};
+
+    thread_local! {
+        pub static CURRENT: Cell<isize> = const { Cell::new(0) };
+        pub static PEAK: Cell<isize> = const { Cell::new(0) };
+        pub static COUNT: Cell<usize> = const { Cell::new(0) };
+        /// Whether the thread is running a measured task.
+        pub static MEASURING: Cell<bool> = const { Cell::new(false) };
+        /// Whether another measurement was nested in the one running on the thread.
+        pub static NESTED: Cell<bool> = const { Cell::new(false) };
+    }
+
+    /// The allocations of all threads which are not running a measured task.
+    pub static OTHERS_CURRENT: AtomicIsize = AtomicIsize::new(0);
+    pub static OTHERS_PEAK: AtomicIsize = AtomicIsize::new(0);
+    pub static OTHERS_COUNT: AtomicUsize = AtomicUsize::new(0);
+    /// The number of measurements in progress and of those started so far.
+    pub static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+    pub static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+    /// Forwards to the system allocator while keeping track of the allocations of each thread.
+    struct Counting;
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    fn record(delta: isize, allocations: usize) {
+        // the thread locals are unavailable while a thread is torn down
+        let _ = CURRENT.try_with(|current| {
+            let value = current.get() + delta;
+            current.set(value);
+            let _ = PEAK.try_with(|peak| peak.set(peak.get().max(value)));
+            let _ = COUNT.try_with(|count| count.set(count.get() + allocations));
+        });
+        if !MEASURING.try_with(Cell::get).unwrap_or(false) {
+            let value = OTHERS_CURRENT.fetch_add(delta, Ordering::Relaxed) + delta;
+            OTHERS_PEAK.fetch_max(value, Ordering::Relaxed);
+            OTHERS_COUNT.fetch_add(allocations, Ordering::Relaxed);
+        }
+    }
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            record(layout.size() as isize, 1);
+            unsafe { System.alloc(layout) }
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            record(layout.size() as isize, 1);
+            unsafe { System.alloc_zeroed(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            record(-(layout.size() as isize), 0);
+            unsafe { System.dealloc(ptr, layout) }
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            record(new_size as isize - layout.size() as isize, 1);
+            unsafe { System.realloc(ptr, layout, new_size) }
+        }
+    }
+}
+
+/// Serializes the tests which measure tasks, as measurements running at the same time make each
+/// other incomplete.
+#[cfg(test)]
+pub static EXCLUSIVE: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512B");
+        assert_eq!(format_bytes(1536), "1.5KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0MiB");
+    }
+
+    #[cfg(feature = "memory")]
+    #[test]
+    fn test_measure() {
+        let _exclusive = EXCLUSIVE.lock().unwrap_or_else(|e| e.into_inner());
+        let (_, memory) = measure(|| vec![0u8; 1 << 20]);
+        let memory = memory.unwrap();
+        assert!(memory.peak >= 1 << 20);
+        assert!(memory.allocations >= 1);
+    }
+
+    #[cfg(feature = "memory")]
+    #[test]
+    fn test_measure_nested() {
+        let _exclusive = EXCLUSIVE.lock().unwrap_or_else(|e| e.into_inner());
+        let ((inner, _), outer) = measure(|| {
+            let outer = vec![0u8; 1 << 20];
+            drop(outer);
+            let inner = measure(|| vec![0u8; 1 << 10]);
+            (inner.1.unwrap(), vec![0u8; 1 << 10])
+        });
+        let outer = outer.unwrap();
+        assert!(inner.peak >= 1 << 10 && inner.peak < 1 << 20);
+        // the peak before the nested measurement is kept and the stolen allocations are marked
+        assert!(outer.peak >= 1 << 20);
+        assert!(!outer.complete);
+        assert_eq!(format!("{outer}").matches('~').count(), 2);
+
+        // the thread counts as measuring until the outer measurement is done
+        let (_, outer) = measure(|| {
+            let _ = measure(|| ());
+            measure(|| ())
+        });
+        assert!(!outer.unwrap().complete);
+    }
+
+    #[cfg(all(feature = "memory", feature = "parallel"))]
+    #[test]
+    fn test_measure_workers() {
+        use rayon::prelude::*;
+        let _exclusive = EXCLUSIVE.lock().unwrap_or_else(|e| e.into_inner());
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let (_, memory) = measure(|| {
+            pool.install(|| {
+                (0..64)
+                    .into_par_iter()
+                    .map(|_| vec![0u8; 1 << 16].len())
+                    .sum::<usize>()
+            })
+        });
+        let memory = memory.unwrap();
+        assert!(memory.complete);
+        assert!(memory.peak >= 1 << 16);
+        assert!(memory.allocations >= 64);
+    }
+}
@@ -0,0 +1,52 @@
+use std::io::Write;
+use std::sync::Mutex;
+
+const WIDTH: usize = 30;
+
+/// A progress bar drawn on the last line of stderr.
+///
+/// Output written through [`Progress::finish`] is printed above the bar, so it may be combined
+/// with live results on stdout.
+pub struct Progress {
+    total: usize,
+    done: Mutex<usize>,
+}
+
+impl Progress {
+    pub fn new(total: usize) -> Self {
+        let progress = Self {
+            total,
+            done: Mutex::new(0),
+        };
+        progress.draw(0, "");
+        progress
+    }
+
+    /// Marks one more item named `label` as done. `print` is called while the bar is cleared.
+    pub fn finish<T>(&self, label: &str, print: impl FnOnce() -> T) -> T {
+        let mut done = self.done.lock().unwrap();
+        *done += 1;
+        Self::clear();
+        let result = print();
+        let _ = std::io::stdout().flush();
+        self.draw(*done, label);
+        result
+    }
+
+    /// Removes the bar once all items are done.
+    pub fn close(self) {
+        let _lock = self.done.lock().unwrap();
+        Self::clear();
+    }
+
+    fn clear() {
+        eprint!("\r\x1b[K");
+    }
+
+    fn draw(&self, done: usize, label: &str) {
+        let filled = done * WIDTH / self.total.max(1);
+        let bar = "#".repeat(filled) + &".".repeat(WIDTH - filled);
+        eprint!("[{bar}] {done}/{} {label}", self.total);
+        let _ = std::io::stderr().flush();
+    }
+}
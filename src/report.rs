@@ -0,0 +1,197 @@
+use crate::answers::Verdict;
+use crate::execute::Outcome;
+use crate::memory::Memory;
+use common::{Day, Task, Year};
+use std::fmt::Display;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, clap_derive::ValueEnum, serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// Human readable lines.
+    #[default]
+    Text,
+    /// One JSON object per line.
+    Json,
+    /// Comma separated values with a header row.
+    Csv,
+    /// Only the answer without status or timing. Failures are printed to stderr instead.
+    Raw,
+}
+
+/// The outcome of a single task execution.
+#[derive(Debug)]
+pub struct Record {
+    pub year: &'static Year,
+    pub day: &'static Day,
+    pub task: &'static Task,
+    /// The user whose input was used if the inputs are separated by user.
+    pub user: Option<String>,
+    pub input: PathBuf,
+    pub outcome: Outcome,
+    pub duration: Duration,
+    /// The heap usage of the task, only measured with the `memory` feature.
+    pub memory: Option<Memory>,
+    pub verdict: Verdict,
+}
+
+/// The flat representation of a [`Record`] used for the structured formats.
+#[derive(serde::Serialize)]
+struct Row<'a> {
+    year: &'a str,
+    day: &'a str,
+    task: &'a str,
+    user: Option<&'a str>,
+    status: &'a str,
+    answer: Option<String>,
+    error: Option<String>,
+    duration_ns: u64,
+    peak_bytes: Option<usize>,
+    allocations: Option<usize>,
+    memory_complete: Option<bool>,
+    input: &'a str,
+    verdict: &'a str,
+    expected: Option<&'a str>,
+}
+
+impl Record {
+    fn row(&self) -> Row<'_> {
+        let (answer, error) = match &self.outcome {
+            Outcome::Ok(ok) => (Some(ok.clone()), None),
+            _ => (None, Some(self.outcome.message())),
+        };
+        let expected = match &self.verdict {
+            Verdict::Fail { expected, .. } => Some(expected.as_str()),
+            _ => None,
+        };
+        Row {
+            year: self.year.name,
+            day: self.day.name,
+            task: self.task.name,
+            user: self.user.as_deref(),
+            status: self.outcome.status(),
+            answer,
+            error,
+            duration_ns: self.duration.as_nanos() as u64,
+            peak_bytes: self.memory.map(|m| m.peak),
+            allocations: self.memory.map(|m| m.allocations),
+            memory_complete: self.memory.map(|m| m.complete),
+            input: self.input.to_str().unwrap_or_default(),
+            verdict: self.verdict.label(),
+            expected,
+        }
+    }
+}
+
+impl Display for Record {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let line = crate::format_detailed(
+            &self.outcome,
+            self.year,
+            self.day,
+            self.task,
+            self.duration,
+            self.memory,
+            &self.verdict,
+        );
+        match &self.user {
+            Some(user) => write!(f, "{user:10} {line}"),
+            None => f.write_str(&line),
+        }
+    }
+}
+
+/// How the records of the text format are delimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lines {
+    /// Every record is followed by the line ending.
+    Terminated(&'static str),
+    /// Records are only separated by newlines, so a single record is printed without one.
+    Separated,
+}
+
+enum Sink<W: Write> {
+    Text {
+        out: W,
+        lines: Lines,
+        /// Whether a record was written before, which a separated record has to be put after.
+        continued: bool,
+    },
+    Json(W),
+    Csv(Box<csv::Writer<W>>),
+    Raw(W),
+}
+
+/// Writes records in the selected format. Records may be reported from multiple threads.
+pub struct Reporter<W: Write> {
+    sink: Mutex<Sink<W>>,
+}
+
+impl<W: Write> Reporter<W> {
+    pub fn new(format: Format, out: W, lines: Lines) -> Self {
+        Self::continuing(format, out, lines, false)
+    }
+
+    /// Creates a reporter for an output which already contains records if `continued` is set,
+    /// i.e. a file that is appended to. Continued outputs get no second CSV header.
+    pub fn continuing(format: Format, out: W, lines: Lines, continued: bool) -> Self {
+        let sink = match format {
+            Format::Text => Sink::Text {
+                out,
+                lines,
+                continued,
+            },
+            Format::Json => Sink::Json(out),
+            Format::Csv => {
+                let writer = csv::WriterBuilder::new()
+                    .has_headers(!continued)
+                    .from_writer(out);
+                Sink::Csv(Box::new(writer))
+            }
+            Format::Raw => Sink::Raw(out),
+        };
+        Self {
+            sink: Mutex::new(sink),
+        }
+    }
+
+    pub fn report(&self, record: &Record) -> std::io::Result<()> {
+        let mut sink = self.sink.lock().unwrap();
+        match &mut *sink {
+            Sink::Text {
+                out,
+                lines,
+                continued,
+            } => {
+                let result = match lines {
+                    Lines::Terminated(end) => write!(out, "{record}{end}"),
+                    Lines::Separated if *continued => write!(out, "\n{record}"),
+                    Lines::Separated => write!(out, "{record}"),
+                };
+                *continued = true;
+                result.and_then(|_| out.flush())
+            }
+            Sink::Json(out) => {
+                serde_json::to_writer(&mut *out, &record.row())?;
+                writeln!(out)
+            }
+            Sink::Csv(out) => {
+                out.serialize(record.row())?;
+                out.flush()
+            }
+            Sink::Raw(out) => match &record.outcome {
+                // multi-line answers start with a newline to separate them from the status
+                Outcome::Ok(ok) => writeln!(out, "{}", ok.trim_start_matches(['\r', '\n'])),
+                _ => {
+                    eprintln!("{record}");
+                    Ok(())
+                }
+            },
+        }
+    }
+}
@@ -1,54 +1,280 @@
+use crate::answers::Answers;
+use crate::execute::{Execution, Outcome};
+use crate::history::{Entry, History};
+use crate::progress::Progress;
+use crate::report::{Format, Lines, Record, Reporter};
+use crate::select::{Matcher, PartNotFound, Pattern};
+use crate::summary::Summary;
+use rayon::ThreadPoolBuilder;
 use rayon::prelude::*;
-use std::convert::Infallible;
-use std::io::{BufReader, Write};
-use std::path::PathBuf;
-use std::time::Duration;
+use std::collections::{HashMap, hash_map};
+use std::io::ErrorKind;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, clap_derive::Parser)]
-pub struct Args;
+pub struct Args {
+    #[clap(
+        help = "Patterns of the form 'YEAR::DAY::TASK' selecting the tasks to run (i.e. '2022::day1*::task2'). If omitted all tasks are run."
+    )]
+    patterns: Vec<Pattern>,
+    #[clap(
+        long,
+        value_parser = Matcher::year,
+        help = "Only runs tasks of the matching years (i.e. 'aoc_2023')."
+    )]
+    year: Option<Matcher>,
+    #[clap(
+        long,
+        value_parser = Matcher::day,
+        help = "Only runs tasks of the matching days (i.e. 'day05..day10')."
+    )]
+    day: Option<Matcher>,
+    #[clap(
+        long,
+        value_parser = Matcher::task,
+        help = "Only runs the matching tasks (i.e. 'task2')."
+    )]
+    task: Option<Matcher>,
+    #[clap(
+        long,
+        action = clap::ArgAction::Set,
+        num_args = 0..=1,
+        require_equals = true,
+        default_value_t = false,
+        default_missing_value = "true",
+        help = "Exit with an error if any result does not match the answers in './YEAR/answers.toml' or './YEAR/answers/USER.toml'. Use '--verify=false' to override the config."
+    )]
+    verify: bool,
+    #[clap(
+        long,
+        value_enum,
+        default_value_t,
+        help = "The format in which results are printed."
+    )]
+    format: Format,
+    #[clap(
+        long,
+        value_parser = crate::parse_duration,
+        help = "Marks tasks running longer than this (i.e. '10s') as timed out."
+    )]
+    timeout: Option<Duration>,
+    #[clap(
+        long,
+        default_value_t = 5,
+        help = "The number of slowest tasks listed in the summary."
+    )]
+    top: usize,
+    #[clap(
+        long,
+        value_parser = crate::parse_duration,
+        help = "Lists all tasks taking longer than this (i.e. '1s') in the summary."
+    )]
+    budget: Option<Duration>,
+    #[clap(flatten)]
+    inputs: crate::inputs::InputArgs,
+    #[clap(
+        long,
+        help = "Buffers all results and prints them sorted by year, day and task once all tasks are done."
+    )]
+    ordered: bool,
+    #[clap(
+        long,
+        help = "Shows a progress bar on stderr while the tasks are running."
+    )]
+    progress: bool,
+    #[clap(
+        short,
+        long,
+        help = "The number of tasks run in parallel. Defaults to the number of CPUs."
+    )]
+    jobs: Option<usize>,
+    #[clap(long, conflicts_with = "jobs", help = "Runs one task after another.")]
+    sequential: bool,
+    #[clap(
+        long,
+        help = "Runs tasks which are parallelized internally one at a time after all other tasks, so their timings are not skewed by other tasks."
+    )]
+    isolate_parallel: bool,
+    #[clap(long, help = "Do not record the results in the history.")]
+    no_history: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    PartNotFound(#[from] PartNotFound),
+    #[error(transparent)]
+    Answers(#[from] crate::answers::Error),
+    #[error(transparent)]
+    Inputs(#[from] crate::inputs::Error),
+    #[error(transparent)]
+    Verification(#[from] crate::VerificationFailed),
+    #[error(transparent)]
+    History(#[from] crate::history::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    ThreadPool(#[from] rayon::ThreadPoolBuildError),
+}
+
+pub fn run(args: Args) -> Result<(), Error> {
+    let filter = Pattern {
+        year: args.year.unwrap_or_default(),
+        day: args.day.unwrap_or_default(),
+        task: args.task.unwrap_or_default(),
+    };
+    let patterns = match args.patterns.is_empty() {
+        true => vec![Pattern::default()],
+        false => args.patterns,
+    };
+    let selected = crate::select::resolve_filtered(crate::YEARS, &patterns, &filter)?;
 
-pub fn run(_: Args) -> Result<(), Infallible> {
-    let tasks = crate::YEARS
+    // without a selected user the tasks are run on the inputs of every user
+    let inputs = args.inputs.inputs();
+    let users = match (inputs.has_users(), args.inputs.user()) {
+        (false, _) => vec![None],
+        (true, Some(user)) => vec![Some(user.to_owned())],
+        (true, None) => inputs.users()?.into_iter().map(Some).collect(),
+    };
+
+    // the answers are loaded once for each year with selected tasks
+    let mut answers = HashMap::new();
+    for user in &users {
+        for &(year, ..) in &selected {
+            if let hash_map::Entry::Vacant(entry) = answers.entry((user.as_deref(), year.name)) {
+                entry.insert(Answers::load(year, user.as_deref())?);
+            }
+        }
+    }
+    let tasks = users
         .iter()
-        .flat_map(move |y| {
-            y.days.iter().flat_map(move |d| {
-                let mut path = PathBuf::from_iter([y.name, "inputs", d.name]);
-                path.set_extension("txt");
-                d.tasks.iter().map(move |t| (y, d, t, path.clone()))
-            })
-        })
-        .collect::<Vec<_>>();
-    let stdout = std::io::stdout();
-    let total_time = tasks
-        .into_par_iter()
-        .map(move |t| {
-            let (year, day, task, path) = t;
-
-            let result;
-            let elapsed;
-            match std::fs::File::open(&path) {
-                Ok(file) => {
-                    let mut buf = BufReader::new(file);
-
-                    let time = std::time::Instant::now();
-                    result = task.run(&mut buf);
-                    elapsed = time.elapsed();
-                }
-                Err(err) => {
-                    result = Err(format!("{err}"));
-                    elapsed = Duration::ZERO;
-                }
-            };
-
-            let _ = stdout.lock().write_fmt(format_args!(
-                "{}\r\n",
-                crate::format_detailed(result, year, day, task, elapsed)
-            ));
-
-            elapsed
+        .flat_map(|user| selected.iter().map(move |s| (user, s)))
+        .map(|(user, &(y, d, t))| {
+            let path = inputs.path(user.as_deref(), y, d)?;
+            let answers = &answers[&(user.as_deref(), y.name)];
+            Ok((y, d, t, user.clone(), answers, path))
         })
-        .sum::<Duration>();
+        .collect::<Result<Vec<_>, crate::inputs::Error>>()?;
+    let reporter = Reporter::new(args.format, std::io::stdout(), Lines::Terminated("\r\n"));
+    let progress = args.progress.then(|| Progress::new(tasks.len()));
+    // the pool also runs the parallel iterators of the tasks, including the isolated ones
+    let pool = match (args.sequential, args.jobs) {
+        (false, Some(jobs)) => Some(Arc::new(
+            ThreadPoolBuilder::new().num_threads(jobs).build()?,
+        )),
+        _ => None,
+    };
+    // threads waiting for a timed task must not be the ones its parallel iterators need to run on
+    let dispatch = match (args.sequential, args.timeout) {
+        (false, Some(_)) => {
+            let jobs = args.jobs.unwrap_or_else(rayon::current_num_threads);
+            Some(ThreadPoolBuilder::new().num_threads(jobs).build()?)
+        }
+        _ => None,
+    };
+    let start = Instant::now();
+    let run_task = |t: (_, _, _, _, &Answers, _)| {
+        let (year, day, task, user, answers, path) = t;
+
+        let (execution, input_hash) = match std::fs::read(&path) {
+            Ok(input) => {
+                let hash = crate::history::hash(&input);
+                let execution = match &pool {
+                    Some(pool) => crate::execute::run_in(pool, task, input, args.timeout),
+                    None => crate::execute::run(task, input, args.timeout),
+                };
+                (execution, Ok(hash))
+            }
+            Err(err) => {
+                let outcome = Outcome::Err(format!("{err}"));
+                (outcome.into(), Err(err.kind()))
+            }
+        };
+        let Execution {
+            outcome,
+            timings,
+            memory,
+            ..
+        } = execution;
+        let missing_input = input_hash == Err(ErrorKind::NotFound);
+
+        let verdict = answers.verify(day, task, &outcome);
+
+        let record = Record {
+            year,
+            day,
+            task,
+            user,
+            input: path,
+            outcome,
+            duration: timings.total(),
+            memory,
+            verdict,
+        };
+        let report = || match args.ordered {
+            true => Ok(()),
+            false => reporter.report(&record),
+        };
+        match &progress {
+            Some(progress) => {
+                let label = format!("{}::{}::{}", year.name, day.name, task.name);
+                progress.finish(&label, report)
+            }
+            None => report(),
+        }?;
+        // runs without an input have nothing to compare against
+        let entry = input_hash.ok().map(|hash| Entry::new(&record, hash));
+        Ok::<_, std::io::Error>(((record, missing_input), entry))
+    };
+
+    let (isolated, tasks): (Vec<_>, Vec<_>) = tasks
+        .into_iter()
+        .partition(|(_, day, ..)| args.isolate_parallel && day.parallel);
+    let run_all = |tasks: Vec<_>| -> Result<Vec<_>, _> {
+        match args.sequential {
+            true => tasks.into_iter().map(run_task).collect(),
+            false => tasks.into_par_iter().map(run_task).collect(),
+        }
+    };
+    let mut results = match (&dispatch, &pool) {
+        (Some(dispatch), _) => dispatch.install(|| run_all(tasks)),
+        (None, Some(pool)) => pool.install(|| run_all(tasks)),
+        (None, None) => run_all(tasks),
+    }?;
+    for task in isolated {
+        results.push(run_task(task)?);
+    }
+    let (mut records, entries): (Vec<_>, Vec<_>) = results.into_iter().unzip();
+    let wall_time = start.elapsed();
+    if let Some(progress) = progress {
+        progress.close();
+    }
+
+    if args.ordered {
+        records.sort_by(|(a, _), (b, _)| {
+            let key = |r: &Record| (r.user.clone(), r.year.name, r.day.name, r.task.name);
+            key(a).cmp(&key(b))
+        });
+        for (record, _) in &records {
+            reporter.report(record)?;
+        }
+    }
+
+    if !args.no_history {
+        History::append(&entries.into_iter().flatten().collect::<Vec<_>>())?;
+    }
+
+    let summary = Summary::new(&records, wall_time, args.top, args.budget);
+    // keep stdout parseable for the structured formats
+    match args.format {
+        Format::Text => print!("{summary}"),
+        _ => eprint!("{summary}"),
+    }
 
-    println!("Finished!\ntotal time: {:?}", total_time);
+    let failed = summary.failed();
+    if args.verify && failed > 0 {
+        return Err(crate::VerificationFailed(failed).into());
+    }
     Ok(())
 }
@@ -0,0 +1,121 @@
+use crate::execute::Outcome;
+use crate::select::{Matcher, PartNotFound, Pattern};
+use crate::stats::Stats;
+use std::path::PathBuf;
+
+#[derive(Debug, clap_derive::Parser)]
+pub struct Args {
+    #[clap(
+        value_parser = Matcher::year,
+        help = "The year of the tasks to be benchmarked. Supports wildcards and ranges."
+    )]
+    year: Matcher,
+    #[clap(
+        value_parser = Matcher::day,
+        help = "The day of the tasks to be benchmarked. Supports wildcards and ranges."
+    )]
+    day: Matcher,
+    #[clap(
+        value_parser = Matcher::task,
+        default_value = "*",
+        help = "The name of the tasks to be benchmarked. Supports wildcards and ranges."
+    )]
+    task: Matcher,
+    #[clap(
+        short = 'n',
+        long,
+        default_value_t = 10,
+        help = "The number of measured runs."
+    )]
+    iterations: usize,
+    #[clap(
+        short,
+        long,
+        default_value_t = 3,
+        help = "The number of runs before measuring starts."
+    )]
+    warmup: usize,
+    #[clap(
+        short,
+        long,
+        help = "The path to the input file. If omitted it is resolved from the inputs layout."
+    )]
+    input: Option<PathBuf>,
+    #[clap(flatten)]
+    inputs: crate::inputs::InputArgs,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    PartNotFound(#[from] PartNotFound),
+    #[error("At least one iteration is required")]
+    NoIterations,
+    #[error(transparent)]
+    Inputs(#[from] crate::inputs::Error),
+}
+
+pub fn run(args: Args) -> Result<(), Error> {
+    if args.iterations == 0 {
+        return Err(Error::NoIterations);
+    }
+    let pattern = Pattern {
+        year: args.year,
+        day: args.day,
+        task: args.task,
+    };
+    let tasks = crate::select::resolve(crate::YEARS, &pattern)?;
+    let inputs = args.inputs.inputs();
+    let user = match &args.input {
+        Some(_) => None,
+        None => inputs.user(args.inputs.user())?,
+    };
+
+    for (year, day, task) in tasks {
+        let path = match &args.input {
+            Some(p) => p.clone(),
+            None => inputs.path(user.as_deref(), year, day)?,
+        };
+        let name = format!("{}::{}::{}", year.name, day.name, task.name);
+
+        // the input is read once so the file system does not influence the measurements
+        let input = match std::fs::read(&path) {
+            Ok(input) => input,
+            Err(e) => {
+                println!("{name}: failed to read {path:?}: {e}\n");
+                continue;
+            }
+        };
+
+        // panics are caught like in `all` so the remaining tasks are still benchmarked
+        let mut outcome = Outcome::Ok(String::new());
+        let mut samples = Vec::with_capacity(args.iterations);
+        for run in 0..args.warmup + args.iterations {
+            let execution = crate::execute::run(task, input.clone(), None);
+            outcome = execution.outcome;
+            if let Outcome::Panic { .. } = outcome {
+                break;
+            }
+            if run >= args.warmup {
+                samples.push(execution.timings);
+            }
+        }
+        let result = match outcome {
+            Outcome::Ok(ok) => Ok(ok),
+            Outcome::Panic { .. } => {
+                println!("{name}: {} {}\n", outcome.status(), outcome.message());
+                continue;
+            }
+            _ => Err(outcome.message()),
+        };
+
+        println!(
+            "{name} ({} runs, {} warm-up)\n{}",
+            args.iterations,
+            args.warmup,
+            crate::format_simple(result)
+        );
+        println!("{}\n", Stats::table(&samples));
+    }
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use crate::select::Pattern;
 use std::{fmt::Display, io::BufReader};
 
 fn get_input<T, E>(prompt: &str) -> T
@@ -43,10 +44,35 @@ where
     }
 }
 
+/// Picks one of the elements, only asking if there is more than one.
+fn pick<T, F, S>(mut elements: Vec<&'static T>, sort: F) -> &'static T
+where
+    T: Display,
+    F: FnMut(&&'static T) -> S,
+    S: Ord,
+{
+    elements.dedup_by(|a, b| std::ptr::eq(*a, *b));
+    match elements.len() {
+        1 => elements[0],
+        _ => select_from_list(elements, sort),
+    }
+}
+
 pub fn run() -> Result<(), std::io::Error> {
-    let year = select_from_list(crate::YEARS, |y| y.name);
-    let day = select_from_list(year.days, |d| d.name);
-    let task = select_from_list(day.tasks, |t| t.name);
+    let prompt = "Enter the task (i.e. 2024/6/2) or leave empty to choose from lists:";
+    let tasks = loop {
+        let pattern: Pattern = get_input(prompt);
+        match crate::select::resolve(crate::YEARS, &pattern) {
+            Ok(tasks) => break tasks,
+            Err(e) => println!("{}", e),
+        }
+    };
+
+    let year = pick(tasks.iter().map(|t| t.0).collect(), |y| y.name);
+    let days = tasks.iter().filter(|t| std::ptr::eq(t.0, year));
+    let day = pick(days.map(|t| t.1).collect(), |d| d.name);
+    let tasks = tasks.iter().filter(|t| std::ptr::eq(t.1, day));
+    let task = pick(tasks.map(|t| t.2).collect(), |t| t.name);
 
     let dir = std::env::current_dir()?;
     let full = loop {
@@ -0,0 +1,84 @@
+use clap::builder::PossibleValuesParser;
+use clap::{Command, CommandFactory};
+use clap_complete::Shell;
+
+#[derive(Debug, clap_derive::Parser)]
+pub struct Args {
+    #[clap(
+        value_enum,
+        help = "The shell to generate the completion script for. (i.e. bash)"
+    )]
+    shell: Shell,
+}
+
+/// The names of the registry offered when completing the argument with the given id.
+fn candidates(id: &str) -> Option<Vec<String>> {
+    let years = crate::YEARS.iter();
+    let mut names = match id {
+        "year" => years
+            .flat_map(|y| [y.name, y.name.trim_start_matches("aoc_")])
+            .map(str::to_owned)
+            .collect::<Vec<_>>(),
+        "day" => years
+            .flat_map(|y| y.days.iter().map(|d| d.name.to_owned()))
+            .collect(),
+        "task" => years
+            .flat_map(|y| y.days.iter())
+            .flat_map(|d| d.tasks.iter().map(|t| t.name.to_owned()))
+            .collect(),
+        _ => return None,
+    };
+    names.sort();
+    names.dedup();
+    Some(names)
+}
+
+/// Attaches the names of the registry to all year, day and task arguments. The values are only
+/// used for completion as the arguments still accept patterns when parsing.
+///
+/// Every argument is touched since `mut_arg` moves the argument to the end, which would otherwise
+/// change the order of the positional arguments.
+fn with_candidates(mut command: Command) -> Command {
+    let names = command
+        .get_subcommands()
+        .map(|c| c.get_name().to_owned())
+        .collect::<Vec<_>>();
+    for name in names {
+        command = command.mut_subcommand(name, |mut sub| {
+            let ids = sub
+                .get_arguments()
+                .map(|a| a.get_id().to_string())
+                .collect::<Vec<_>>();
+            for id in ids {
+                let names = candidates(&id);
+                sub = sub.mut_arg(id, |a| match names {
+                    Some(names) => a.value_parser(PossibleValuesParser::new(names)),
+                    None => a,
+                });
+            }
+            sub
+        });
+    }
+    command
+}
+
+pub fn run(args: Args) -> Result<(), std::io::Error> {
+    let mut command = with_candidates(crate::Args::command());
+    let name = command.get_name().to_owned();
+    clap_complete::generate(args.shell, &mut command, name, &mut std::io::stdout());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        let mut command = with_candidates(crate::Args::command());
+        let mut script = Vec::new();
+        clap_complete::generate(Shell::Bash, &mut command, "aoc", &mut script);
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains("aoc_2022") && script.contains("day25"));
+    }
+}
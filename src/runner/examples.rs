@@ -0,0 +1,124 @@
+use crate::answers::Verdict;
+use crate::examples::Example;
+use crate::execute::{Execution, Outcome};
+use crate::select::{Matcher, PartNotFound, Pattern};
+
+#[derive(Debug, clap_derive::Parser)]
+pub struct Args {
+    #[clap(
+        value_parser = Matcher::year,
+        help = "The year of the tasks to be run. (i.e. aoc_2022)"
+    )]
+    year: Matcher,
+    #[clap(
+        value_parser = Matcher::day,
+        help = "The day of the tasks to be run. (i.e. day01)"
+    )]
+    day: Matcher,
+    #[clap(
+        value_parser = Matcher::task,
+        default_value = "*",
+        help = "The name of the tasks to be run. (i.e. task1)"
+    )]
+    task: Matcher,
+    #[clap(
+        long,
+        action = clap::ArgAction::Set,
+        num_args = 0..=1,
+        require_equals = true,
+        default_value_t = false,
+        default_missing_value = "true",
+        help = "Exit with an error if any result does not match its '.expected' file. Use '--verify=false' to override the config."
+    )]
+    verify: bool,
+    #[clap(
+        long,
+        value_parser = crate::parse_duration,
+        help = "Marks tasks running longer than this (i.e. '10s') as timed out."
+    )]
+    timeout: Option<std::time::Duration>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    PartNotFound(#[from] PartNotFound),
+    #[error(transparent)]
+    Verification(#[from] crate::VerificationFailed),
+}
+
+pub fn run(args: Args) -> Result<(), Error> {
+    let pattern = Pattern {
+        year: args.year,
+        day: args.day,
+        task: args.task,
+    };
+    let tasks = crate::select::resolve(crate::YEARS, &pattern)?;
+
+    println!(
+        "{:24} {:16} {:7} {:7} {:10} ANSWER",
+        "TASK", "EXAMPLE", "STATUS", "VERDICT", "TIME"
+    );
+    let (mut passed, mut failed, mut unknown) = (0, 0, 0);
+    let mut missing = Vec::new();
+    for (year, day, task) in tasks {
+        let examples = Example::list(year, day);
+        if examples.is_empty() {
+            let dir = Example::dir(year, day);
+            if !missing.contains(&dir) {
+                missing.push(dir);
+            }
+            continue;
+        }
+
+        for example in examples {
+            let Execution {
+                outcome, timings, ..
+            } = match std::fs::read(&example.input) {
+                Ok(input) => crate::execute::run(task, input, args.timeout),
+                Err(e) => Outcome::Err(e.to_string()).into(),
+            };
+            let verdict = match example.expected(task) {
+                Some(expected) => crate::answers::compare(&expected, &outcome),
+                None => Verdict::Unknown,
+            };
+            match verdict {
+                Verdict::Pass => passed += 1,
+                Verdict::Fail { .. } => failed += 1,
+                Verdict::Unknown => unknown += 1,
+            }
+
+            let name = format!("{}::{}::{}", year.name, day.name, task.name);
+            let status = outcome.status();
+            let label = verdict.label();
+            let duration = crate::format_duration(timings.total());
+            let message = outcome.message();
+            let message = message.trim_matches(['\r', '\n']);
+
+            // multi-line answers are printed below the row
+            match message.contains('\n') {
+                false => println!(
+                    "{name:24} {:16} {status:7} {label:7} {duration:10} {message}",
+                    example.name
+                ),
+                true => println!(
+                    "{name:24} {:16} {status:7} {label:7} {duration:10}\n{message}",
+                    example.name
+                ),
+            }
+            if let Verdict::Fail { expected, .. } = &verdict {
+                println!("  expected: {expected}");
+            }
+        }
+    }
+    println!("\n{passed} passed, {failed} failed, {unknown} without expected answer");
+    if !missing.is_empty() {
+        println!("No examples found in:");
+        missing.iter().for_each(|dir| println!("  {dir:?}"));
+    }
+
+    if args.verify && failed > 0 {
+        return Err(crate::VerificationFailed(failed).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use crate::history::{Change, Entry, History};
+use crate::select::Pattern;
+use std::collections::HashMap;
+
+#[derive(Debug, clap_derive::Parser)]
+pub struct Args {
+    #[clap(
+        help = "Patterns of the form 'YEAR::DAY::TASK' selecting the tasks to list (i.e. '2022::day1*'). If omitted all tasks are listed."
+    )]
+    patterns: Vec<Pattern>,
+    #[clap(
+        short = 'n',
+        long,
+        default_value_t = 20,
+        help = "The number of most recent runs listed."
+    )]
+    limit: usize,
+    #[clap(
+        long,
+        default_value_t = 20.0,
+        help = "The increase of the duration in percent above which a run is flagged as a regression."
+    )]
+    threshold: f64,
+    #[clap(long, help = "Only lists runs whose answer or timing changed.")]
+    changes: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    History(#[from] crate::history::Error),
+}
+
+pub fn run(args: Args) -> Result<(), Error> {
+    let entries = History::load()?;
+
+    // every run is compared against the previous run of the same task on the same input
+    let mut previous = HashMap::<_, &Entry>::new();
+    let mut runs = Vec::new();
+    for entry in &entries {
+        let changes = match previous.insert(entry.key(), entry) {
+            Some(prev) => crate::history::compare(prev, entry, args.threshold),
+            None => Vec::new(),
+        };
+        let selected = args.patterns.is_empty()
+            || args
+                .patterns
+                .iter()
+                .any(|p| p.matches_names(&entry.year, &entry.day, &entry.task));
+        if selected && (!args.changes || !changes.is_empty()) {
+            runs.push((entry, changes));
+        }
+    }
+
+    if entries.is_empty() {
+        println!("No runs recorded in {:?}", History::path());
+        return Ok(());
+    }
+    if runs.is_empty() {
+        println!("No matching runs among the {} recorded ones", entries.len());
+        return Ok(());
+    }
+    for (entry, changes) in &runs[runs.len().saturating_sub(args.limit)..] {
+        let time = format_timestamp(entry.timestamp);
+        let duration = crate::format_duration(entry.duration());
+        let name = format!("{}::{}::{}", entry.year, entry.day, entry.task);
+        let user = entry.user.as_deref().unwrap_or("-");
+        let answer = entry.answer.trim_matches(['\r', '\n']);
+        println!(
+            "{time} {:7} [{duration:9}] {name:22} {user:10} {} {answer}",
+            entry.status,
+            entry.input_hash.get(..8).unwrap_or(&entry.input_hash)
+        );
+        for change in changes {
+            match change {
+                Change::Answer { previous } => {
+                    let previous = previous.trim_matches(['\r', '\n']);
+                    println!("  answer changed, previously: {previous}")
+                }
+                Change::Regression { percent } => {
+                    println!("  {percent}% slower than the previous run")
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Formats seconds since the unix epoch as `YYYY-MM-DD HH:MM:SS` in UTC.
+fn format_timestamp(timestamp: u64) -> String {
+    let (days, secs) = (timestamp / 86400, timestamp % 86400);
+    // converts days since the epoch to a civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    let (h, m, s) = (secs / 3600, (secs / 60) % 60, secs % 60);
+    format!("{year:04}-{month:02}-{day:02} {h:02}:{m:02}:{s:02}")
+}
@@ -1,105 +1,466 @@
-use std::fs::File;
-use std::io::stdout;
-use std::{io::BufReader, path::PathBuf};
+use crate::answers::{Answers, Verdict};
+use crate::execute::{Execution, Outcome};
+use crate::history::{Entry, History};
+use crate::inputs::Inputs;
+use crate::report::{Format, Lines, Record, Reporter};
+use crate::select::{InvalidPattern, Matcher, PartNotFound, Pattern, Selection};
+use crate::stats::Stats;
+use common::{Day, Task, Timings, Year};
+use std::collections::{HashMap, hash_map};
+use std::fs::{File, OpenOptions};
+use std::io::{Cursor, Read, Write, stdout};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 #[derive(Debug, clap_derive::Parser)]
 pub struct Args {
-    #[clap(help = "The year of the task to be run. (i.e. inputs)")]
+    #[clap(
+        help = "The year of the task to be run (i.e. aoc_2022 or 2022) or the whole task (i.e. 2022/1/2 or 22.1.b)."
+    )]
     year: String,
-    #[clap(help = "The day of the task to be run. (i.e. day01)")]
-    day: String,
-    #[clap(help = "The name of the task to be run. (i.e. task1)")]
-    task: String,
+    #[clap(
+        help = "The day of the task to be run. (i.e. day01 or 1) Omitted if given by the year."
+    )]
+    day: Option<String>,
+    #[clap(
+        help = "The name of the task to be run. (i.e. task1, 1 or a) Omitted if given by the year."
+    )]
+    task: Option<String>,
+    #[clap(value_name = "INPUT", help = "Same as '--input'.")]
+    input_arg: Option<String>,
     #[clap(
         short,
         long,
-        help = "The path to the input file. If omitted it will be assumed to './YEAR/inputs/DAY.txt'."
+        help = "The path to the input file or '-' to read from stdin. If omitted it is resolved from the inputs layout."
     )]
     input: Option<PathBuf>,
+    #[clap(
+        long,
+        conflicts_with = "input",
+        help = "The input given literally instead of reading it from a file."
+    )]
+    input_str: Option<String>,
     #[clap(
         short,
         long,
-        help = "The path to the output file. If omitted the result will be written to stdout."
+        help = "The path to the output file. If omitted the result will be written to stdout. May contain the placeholders '{year}', '{day}' and '{task}' to write one file per task. If several tasks are selected without placeholders the path is used as a directory."
     )]
     output: Option<PathBuf>,
-}
-
-#[derive(Debug, thiserror::Error)]
-pub enum PartNotFound {
-    #[error("Failed to find year {0}")]
-    Year(String),
-    #[error("Failed to find day {0}")]
-    Day(String),
-    #[error("Failed to find task {0}")]
-    Task(String),
+    #[clap(
+        long,
+        requires = "output",
+        help = "Appends to the output file instead of overwriting it."
+    )]
+    append: bool,
+    #[clap(
+        long,
+        action = clap::ArgAction::Set,
+        num_args = 0..=1,
+        require_equals = true,
+        default_value_t = false,
+        default_missing_value = "true",
+        help = "Exit with an error if the result does not match the answer in './YEAR/answers.toml'. Use '--verify=false' to override the config."
+    )]
+    verify: bool,
+    #[clap(
+        long,
+        value_enum,
+        default_value_t,
+        help = "The format in which the result is printed. Use 'raw' to print only the answer."
+    )]
+    format: Format,
+    #[clap(
+        long,
+        value_parser = crate::parse_duration,
+        help = "Marks the task as timed out if it runs longer than this (i.e. '10s')."
+    )]
+    timeout: Option<std::time::Duration>,
+    #[clap(flatten)]
+    inputs: crate::inputs::InputArgs,
+    #[clap(long, help = "Do not record the results in the history.")]
+    no_history: bool,
+    #[clap(
+        long,
+        help = "Print the time spent in the spans of each task to stderr. Requires the 'profile' feature to list more than the parse and solve phases."
+    )]
+    profile: bool,
+    #[clap(
+        long,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Runs each task this many times on the same input and prints the aggregated timings to stderr. The reported duration is the median. Useful to give external profilers a steady loop."
+    )]
+    repeat: Option<u64>,
+    #[clap(
+        long,
+        conflicts_with = "repeat",
+        value_parser = crate::parse_duration,
+        help = "Same as '--repeat' but runs each task repeatedly for the given time (i.e. '10s')."
+    )]
+    repeat_for: Option<std::time::Duration>,
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
+    #[error(transparent)]
+    InvalidPattern(#[from] InvalidPattern),
+    #[error("Unexpected argument '{0}', the task and its input are already given")]
+    UnexpectedArgument(String),
+    #[error("The input '{0}' cannot be used with '--input' or '--input-str'")]
+    ConflictingInput(String),
     #[error(transparent)]
     PartNotFound(#[from] PartNotFound),
     #[error("Failed to find file '{0:?}'")]
     FileNotFound(PathBuf),
+    #[error("Several tasks would be written to '{0:?}', add '{{task}}' to the output path")]
+    SharedOutput(PathBuf),
     #[error(transparent)]
     IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    Answers(#[from] crate::answers::Error),
+    #[error(transparent)]
+    Inputs(#[from] crate::inputs::Error),
+    #[error(transparent)]
+    Verification(#[from] crate::VerificationFailed),
+    #[error(transparent)]
+    History(#[from] crate::history::Error),
 }
 
-pub fn run(args: Args) -> Result<(), Error> {
-    let y = crate::YEARS.iter().filter(|y| y.name == args.year).next();
+/// Where the input of the selected tasks is read from.
+enum Source {
+    Default {
+        inputs: Inputs,
+        user: Option<String>,
+    },
+    File(PathBuf),
+    /// The content of stdin which is read once and shared by all selected tasks.
+    Stdin(Vec<u8>),
+    Inline(String),
+}
 
-    let year = match y {
-        Some(y) => y,
-        None => return Err(PartNotFound::Year(args.year.to_owned()).into()),
-    };
+impl Args {
+    /// The selected tasks. The positional arguments after the year are only read as day and task
+    /// as long as the year does not already give them, the next one is the input.
+    fn selection(&mut self) -> Result<Pattern, Error> {
+        let mut pattern = self.year.parse::<Pattern>()?;
+        let given = Pattern::parts(&self.year).len();
+        let mut rest = [self.day.take(), self.task.take(), self.input_arg.take()]
+            .into_iter()
+            .flatten();
+        if given < 2
+            && let Some(day) = rest.next()
+        {
+            pattern.day = Matcher::day(&day)?;
+        }
+        if given < 3
+            && let Some(task) = rest.next()
+        {
+            pattern.task = Matcher::task(&task)?;
+        }
+        if let Some(input) = rest.next() {
+            if self.input.is_some() || self.input_str.is_some() {
+                return Err(Error::ConflictingInput(input));
+            }
+            self.input = Some(PathBuf::from(input));
+        }
+        match rest.next() {
+            Some(arg) => Err(Error::UnexpectedArgument(arg)),
+            None => Ok(pattern),
+        }
+    }
+}
 
-    let d = year.days.into_iter().filter(|d| d.name == args.day).next();
+impl Source {
+    fn new(args: &mut Args) -> Result<Self, Error> {
+        if let Some(input) = args.input_str.take() {
+            return Ok(Self::Inline(input));
+        }
+        let source = match args.input.take() {
+            None => {
+                let inputs = args.inputs.inputs();
+                let user = inputs.user(args.inputs.user())?;
+                Self::Default { inputs, user }
+            }
+            Some(path) if path.as_os_str() == "-" => {
+                let mut input = Vec::new();
+                std::io::stdin().read_to_end(&mut input)?;
+                Self::Stdin(input)
+            }
+            Some(path) => Self::File(path),
+        };
+        Ok(source)
+    }
 
-    let day = match d {
-        Some(d) => d,
-        None => return Err(PartNotFound::Day(args.day.to_owned()).into()),
-    };
+    /// Returns the input of the given day and a path describing where it was read from.
+    fn read(&self, year: &Year, day: &Day) -> Result<(PathBuf, Vec<u8>), Error> {
+        let path = match self {
+            Source::Default { inputs, user } => inputs.path(user.as_deref(), year, day)?,
+            Source::File(path) => path.clone(),
+            Source::Stdin(input) => return Ok((PathBuf::from("-"), input.clone())),
+            Source::Inline(input) => return Ok((PathBuf::new(), input.clone().into_bytes())),
+        };
+        match std::fs::read(&path) {
+            Ok(input) => Ok((path, input)),
+            Err(_) => Err(Error::FileNotFound(path)),
+        }
+    }
+}
+
+/// Opens the output file and returns whether it already contains records that are appended to.
+fn open_output(path: &Path, append: bool) -> std::io::Result<(File, bool)> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)?;
+    let continued = append && file.metadata()?.len() > 0;
+    Ok((file, continued))
+}
 
-    let t = day.tasks.into_iter().filter(|d| d.name == args.task).next();
+/// The output path of a task given by a template with placeholders.
+fn expand(template: &str, year: &Year, day: &Day, task: &Task) -> PathBuf {
+    let path = template
+        .replace("{year}", year.name)
+        .replace("{day}", day.name)
+        .replace("{task}", task.name);
+    PathBuf::from(path)
+}
+
+/// Ensures the template gives each task its own file, as the tasks would otherwise truncate the
+/// output of each other.
+fn check_template(template: &str, tasks: &[Selection]) -> Result<(), Error> {
+    let mut paths = std::collections::HashSet::new();
+    for (year, day, task) in tasks {
+        let path = expand(template, year, day, task);
+        if !paths.insert(path.clone()) {
+            return Err(Error::SharedOutput(path));
+        }
+    }
+    Ok(())
+}
+
+/// How often each task is run by `--repeat` or `--repeat-for`.
+#[derive(Debug, Clone, Copy)]
+enum Repeat {
+    Runs(u64),
+    For(Duration),
+}
+
+impl Repeat {
+    /// Runs the task on the input until enough runs are done or the time is up. The timings of
+    /// the already finished first run are included.
+    ///
+    /// The task is called directly on a fresh cursor over the same input to keep the loop free of
+    /// any overhead for external profilers.
+    fn run(self, task: &Task, input: &[u8], first: Timings) -> Vec<Timings> {
+        let start = Instant::now();
+        let mut samples = vec![first];
+        loop {
+            let done = match self {
+                Repeat::Runs(runs) => samples.len() as u64 >= runs,
+                Repeat::For(time) => start.elapsed() >= time,
+            };
+            if done {
+                break samples;
+            }
+            let (_, timings) = task.run_timed(&mut Cursor::new(input));
+            samples.push(timings);
+        }
+    }
+}
 
-    let task = match t {
-        Some(d) => d,
-        None => return Err(PartNotFound::Task(args.task.to_owned()).into()),
+pub fn run(mut args: Args) -> Result<(), Error> {
+    let pattern = args.selection()?;
+    let source = Source::new(&mut args)?;
+    let tasks = crate::select::resolve(crate::YEARS, &pattern)?;
+
+    // several tasks are written to separate files unless the path already says otherwise
+    let template = args.output.map(|path| {
+        let template = path.to_string_lossy().into_owned();
+        match tasks.len() > 1 && !template.contains('{') {
+            true => format!("{template}/{{year}}/{{day}}_{{task}}.txt"),
+            false => template,
+        }
+    });
+    let shared: Option<Reporter<Box<dyn Write>>> = match &template {
+        None => Some(Reporter::new(
+            args.format,
+            Box::new(stdout().lock()),
+            Lines::Separated,
+        )),
+        Some(template) if !template.contains('{') => {
+            let (file, continued) = open_output(Path::new(template), args.append)?;
+            let file = Box::new(file);
+            Some(Reporter::continuing(
+                args.format,
+                file,
+                Lines::Separated,
+                continued,
+            ))
+        }
+        Some(template) => {
+            check_template(template, &tasks)?;
+            None
+        }
     };
 
-    let input_path = match args.input {
-        Some(p) => p,
-        None => PathBuf::from(format!("{}/inputs/{}.txt", args.year, args.day)),
+    if args.profile && !common::profile::ENABLED {
+        eprintln!("Built without the 'profile' feature, only parse and solve are timed.");
+    }
+
+    let repeat = match (args.repeat, args.repeat_for) {
+        (Some(runs), _) => Some(Repeat::Runs(runs)),
+        (_, Some(time)) => Some(Repeat::For(time)),
+        (None, None) => None,
     };
 
-    let file = File::open(&input_path).map_err(move |_| Error::FileNotFound(input_path))?;
+    // the stored answers only apply to the default inputs and are loaded once per year
+    let mut answers = HashMap::new();
+    if let Source::Default { user, .. } = &source {
+        for &(year, ..) in &tasks {
+            if let hash_map::Entry::Vacant(entry) = answers.entry(year.name) {
+                entry.insert(Answers::load(year, user.as_deref())?);
+            }
+        }
+    }
 
-    let mut buf = BufReader::new(file);
+    let several = tasks.len() > 1;
+    let mut failed = 0;
+    let mut entries = Vec::new();
+    for (year, day, task) in tasks {
+        let (input_path, input) = match source.read(year, day) {
+            Ok((path, input)) => (path, Some(input)),
+            // like `all` a missing input only fails its own task if several are selected
+            Err(Error::FileNotFound(path)) if several => (path, None),
+            Err(e) => return Err(e),
+        };
 
-    let time = std::time::Instant::now();
-    let result = task.run(&mut buf);
-    let elapsed = time.elapsed();
+        let input_hash = input.as_deref().map(crate::history::hash);
+        // the input is only kept for the repetitions if there are any
+        let repeated = repeat.and_then(|repeat| Some((repeat, input.clone()?)));
+        let Execution {
+            outcome,
+            mut timings,
+            memory,
+            spans,
+        } = match input {
+            Some(input) => crate::execute::run(task, input, args.timeout),
+            None => Outcome::Err(Error::FileNotFound(input_path.clone()).to_string()).into(),
+        };
 
-    let mut file_out;
-    let mut std_out;
-    let output: &mut dyn std::io::Write;
-    match args.output {
-        None => {
-            std_out = stdout().lock();
-            output = &mut std_out;
+        // failures are not repeated as they would only end the same way again
+        let samples = match (repeated, &outcome) {
+            (Some((repeat, input)), Outcome::Ok(_)) => repeat.run(task, &input, timings),
+            _ => vec![timings],
+        };
+        if samples.len() > 1 {
+            let stats = |f: fn(&Timings) -> Duration| Stats::new(samples.iter().map(f).collect());
+            timings = Timings {
+                parse: stats(|t| t.parse).median(),
+                solve: stats(|t| t.solve).median(),
+            };
         }
-        Some(path) => match File::open(path.clone()) {
-            Err(e) => return Err(Error::IoError(e)),
-            Ok(f) => {
-                file_out = f;
-                output = &mut file_out;
+
+        let verdict = match answers.get(year.name) {
+            Some(answers) => answers.verify(day, task, &outcome),
+            None => Verdict::Unknown,
+        };
+        failed += verdict.is_fail() as usize;
+
+        let record = Record {
+            year,
+            day,
+            task,
+            user: match &source {
+                Source::Default { user, .. } => user.clone(),
+                _ => None,
+            },
+            input: input_path,
+            outcome,
+            duration: timings.total(),
+            memory,
+            verdict,
+        };
+        // runs without an input have nothing to compare against
+        if let Some(hash) = input_hash {
+            entries.push(Entry::new(&record, hash));
+        }
+        match (&shared, &template) {
+            (Some(reporter), _) => reporter.report(&record)?,
+            (None, Some(template)) => {
+                let path = expand(template, year, day, task);
+                let (file, continued) = open_output(&path, args.append)?;
+                Reporter::continuing(args.format, file, Lines::Separated, continued)
+                    .report(&record)?;
             }
-        },
-    };
+            (None, None) => unreachable!("stdout is always shared"),
+        }
+        if args.profile {
+            eprintln!("{}", crate::format_profile(&spans, timings));
+        }
+        if samples.len() > 1 {
+            let total = samples.iter().map(Timings::total).sum::<Duration>();
+            let runs = samples.len();
+            eprintln!("{runs} runs in {}", crate::format_duration(total));
+            eprintln!("{}", Stats::table(&samples));
+        }
+    }
+    if !args.no_history {
+        History::append(&entries)?;
+    }
 
-    write!(
-        output,
-        "{}",
-        crate::format_detailed(result, year, day, task, elapsed)
-    )?;
+    if args.verify && failed > 0 {
+        return Err(crate::VerificationFailed(failed).into());
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_template() {
+        let pattern = "2022::day01".parse().unwrap();
+        let tasks = crate::select::resolve(crate::YEARS, &pattern).unwrap();
+        assert!(check_template("out/{day}_{task}.txt", &tasks).is_ok());
+        let err = check_template("out/{day}.txt", &tasks).unwrap_err();
+        assert!(matches!(err, Error::SharedOutput(path) if path == Path::new("out/day01.txt")));
+    }
+
+    fn selection(args: &[&str]) -> (Result<Pattern, Error>, Option<PathBuf>) {
+        let args = ["aoc", "run"].iter().chain(args);
+        let crate::Args::Run(mut args) = <crate::Args as clap::Parser>::parse_from(args) else {
+            panic!("expected the run command");
+        };
+        (args.selection(), args.input)
+    }
+
+    #[test]
+    fn test_selection() {
+        let expected = "2023::day01::task1".parse::<Pattern>().unwrap();
+        for (args, input) in [
+            (&["2023/1/1", "-"][..], Some("-")),
+            (
+                &["2023/1/1", "aoc_2023/inputs/day01.txt"],
+                Some("aoc_2023/inputs/day01.txt"),
+            ),
+            (&["2023/1", "1", "-"], Some("-")),
+            (&["2023", "1", "1", "-"], Some("-")),
+            (&["2023", "1", "1"], None),
+            (&["2023/1/1", "--input", "-"], Some("-")),
+        ] {
+            let (pattern, path) = selection(args);
+            assert_eq!(pattern.unwrap(), expected, "{args:?}");
+            assert_eq!(path.as_deref(), input.map(Path::new), "{args:?}");
+        }
+
+        let (pattern, _) = selection(&["2023/1/1", "a", "b"]);
+        assert!(matches!(pattern, Err(Error::UnexpectedArgument(arg)) if arg == "b"));
+        let (pattern, _) = selection(&["2023/1/1", "-", "--input-str", "1abc2"]);
+        assert!(matches!(pattern, Err(Error::ConflictingInput(_))));
+    }
+}
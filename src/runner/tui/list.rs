@@ -0,0 +1,157 @@
+use crossterm::event::KeyCode;
+use std::fmt::Display;
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::widgets::{Block, BorderType, Borders, List, ListItem, ListState, StatefulWidget, Widget};
+
+/// Text typed after `/` to narrow down a list.
+#[derive(Debug, Default)]
+pub struct Filter {
+    text: String,
+    editing: bool,
+}
+
+impl Filter {
+    /// Handles a key while the filter is edited and returns whether the key was consumed.
+    /// Navigation keys and Enter are passed on so the list can still be used while typing.
+    pub fn edit(&mut self, code: KeyCode) -> bool {
+        match (self.editing, code) {
+            (false, KeyCode::Char('/')) => self.editing = true,
+            (false, KeyCode::Esc) if !self.text.is_empty() => self.text.clear(),
+            (false, _) => return false,
+            (true, KeyCode::Char(c)) => self.text.push(c),
+            (true, KeyCode::Backspace) => {
+                if self.text.pop().is_none() {
+                    self.editing = false;
+                }
+            }
+            (true, KeyCode::Esc) => {
+                self.text.clear();
+                self.editing = false;
+            }
+            (true, KeyCode::Enter) => {
+                self.editing = false;
+                return false;
+            }
+            (true, _) => return false,
+        }
+        true
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn matches(&self, s: &str) -> bool {
+        s.to_lowercase().contains(&self.text.to_lowercase())
+    }
+
+    /// The filter as shown after the title of the list.
+    pub fn title(&self) -> String {
+        match (self.editing, self.is_empty()) {
+            (true, _) => format!(" /{}_", self.text),
+            (false, true) => String::new(),
+            (false, false) => format!(" /{}", self.text),
+        }
+    }
+}
+
+/// Moves the selection of a list with `len` rows of which `page` are visible at once. Returns
+/// whether the key was a navigation key.
+pub fn navigate(state: &mut ListState, len: usize, page: usize, code: KeyCode) -> bool {
+    let last = len.saturating_sub(1);
+    let current = state.selected().unwrap_or_default().min(last);
+    let next = match code {
+        KeyCode::Up if current == 0 => last,
+        KeyCode::Up => current - 1,
+        KeyCode::Down if current == last => 0,
+        KeyCode::Down => current + 1,
+        KeyCode::PageUp => current.saturating_sub(page),
+        KeyCode::PageDown => (current + page).min(last),
+        KeyCode::Home => 0,
+        KeyCode::End => last,
+        _ => return false,
+    };
+    state.select((len > 0).then_some(next));
+    true
+}
+
+/// Moves the first visible line of a scrolled text with `len` lines by the key.
+pub fn scroll(offset: usize, len: usize, page: usize, code: KeyCode) -> usize {
+    let last = len.saturating_sub(1);
+    match code {
+        KeyCode::Up => offset.saturating_sub(1),
+        KeyCode::Down => offset + 1,
+        KeyCode::PageUp => offset.saturating_sub(page),
+        KeyCode::PageDown => offset + page,
+        KeyCode::Home => 0,
+        KeyCode::End => last,
+        _ => offset,
+    }
+    .min(last)
+}
+
+/// The number of rows visible in a list with a border drawn in `area`.
+pub fn page_size(area: Rect) -> usize {
+    (area.height as usize).saturating_sub(2).max(1)
+}
+
+pub struct StatefulList<T> {
+    pub title: String,
+    pub state: ListState,
+    pub items: Vec<T>,
+    pub filter: Filter,
+    /// The indices of the items matching the filter.
+    pub visible: Vec<usize>,
+    pub page: usize,
+}
+
+impl<T: Display> StatefulList<T> {
+    pub fn new(title: String, items: Vec<T>) -> Self {
+        let mut list = Self {
+            title,
+            state: ListState::default(),
+            items,
+            filter: Filter::default(),
+            visible: Vec::new(),
+            page: 1,
+        };
+        list.update_visible();
+        list
+    }
+
+    pub fn update_visible(&mut self) {
+        self.visible = (0..self.items.len())
+            .filter(|&i| self.filter.matches(&self.items[i].to_string()))
+            .collect();
+        self.state.select((!self.visible.is_empty()).then_some(0));
+    }
+
+    /// The index of the selected item.
+    pub fn selected(&self) -> Option<usize> {
+        self.visible.get(self.state.selected()?).copied()
+    }
+}
+
+impl<T: Display> Widget for &mut StatefulList<T> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.page = page_size(area);
+        let items = self
+            .visible
+            .iter()
+            .map(|&i| ListItem::new(format!("{}", self.items[i])))
+            .collect::<Vec<_>>();
+        let title = format!("{}{}", self.title, self.filter.title());
+        let list = List::new(items)
+            .block(block(&title))
+            .highlight_symbol(" >> ");
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
+
+pub fn block(title: &str) -> Block<'_> {
+    Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+}
@@ -0,0 +1,170 @@
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+
+/// Whether the line underlines the previous one to make it a heading, i.e. `-----`.
+fn is_underline(line: &str) -> bool {
+    let line = line.trim();
+    line.len() >= 2 && (line.chars().all(|c| c == '-') || line.chars().all(|c| c == '='))
+}
+
+fn heading(text: &str) -> Spans<'static> {
+    let style = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+    Spans::from(Span::styled(text.trim().to_owned(), style))
+}
+
+/// Renders the subset of markdown used by the puzzle descriptions: headings, lists, code blocks
+/// and inline emphasis, code and links. Runs of blank lines are collapsed into one.
+pub fn render(text: &str) -> Vec<Spans<'static>> {
+    let code = Style::default().fg(Color::Yellow);
+    let mut lines = Vec::new();
+    let mut in_code = false;
+    let mut source = text.lines().peekable();
+    while let Some(line) = source.next() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            lines.push(Spans::from(Span::styled(format!("  {line}"), code)));
+            continue;
+        }
+        if line.trim().is_empty() {
+            if lines.last().is_some_and(|l: &Spans| l.width() > 0) {
+                lines.push(Spans::default());
+            }
+            continue;
+        }
+        if let Some(text) = line.strip_prefix('#') {
+            lines.push(heading(text.trim_start_matches('#')));
+            continue;
+        }
+        if source.peek().is_some_and(|next| is_underline(next)) {
+            source.next();
+            lines.push(heading(line));
+            continue;
+        }
+        let list_item = ["* ", "- "]
+            .iter()
+            .find_map(|marker| line.strip_prefix(marker));
+        match list_item {
+            Some(item) => {
+                let mut spans = vec![Span::raw("  • ")];
+                spans.extend(inline(item));
+                lines.push(Spans::from(spans));
+            }
+            None => lines.push(Spans::from(inline(line))),
+        }
+    }
+    lines
+}
+
+/// Splits the text into spans styled by `**strong**`, `*emphasis*`, `` `code` `` and
+/// `[links](url)` of which only the text is shown.
+fn inline(text: &str) -> Vec<Span<'static>> {
+    const STRONG: usize = 0;
+    const EMPHASIS: usize = 1;
+    const CODE: usize = 2;
+    const LINK: usize = 3;
+    let style = |open: [bool; 4]| {
+        let mut style = Style::default();
+        if open[STRONG] || open[EMPHASIS] {
+            style = style.fg(Color::White).add_modifier(Modifier::BOLD);
+        }
+        if open[EMPHASIS] {
+            style = style.add_modifier(Modifier::ITALIC);
+        }
+        if open[CODE] {
+            style = style.fg(Color::Yellow);
+        }
+        if open[LINK] {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+        style
+    };
+
+    let mut spans = Vec::new();
+    let mut current = String::new();
+    let mut open = [false; 4];
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        // the toggled style and the length of its marker
+        let marker = match c {
+            '`' => Some((CODE, 1)),
+            _ if open[CODE] => None,
+            // unlike the `*` of `2 * 3` a marker has to be closed later on to open a style
+            '*' if rest.starts_with("**") => {
+                (open[STRONG] || opens(&rest[2..], "**")).then_some((STRONG, 2))
+            }
+            '*' if open[EMPHASIS] || opens(&rest[1..], "*") => Some((EMPHASIS, 1)),
+            '[' if !open[LINK] && rest.contains("](") => Some((LINK, 1)),
+            // the url of a link is skipped
+            ']' if open[LINK] && rest.starts_with("](") => {
+                Some((LINK, rest.find(')').map_or(rest.len(), |end| end + 1)))
+            }
+            _ => None,
+        };
+        let Some((toggled, len)) = marker else {
+            current.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+        if !current.is_empty() {
+            spans.push(Span::styled(std::mem::take(&mut current), style(open)));
+        }
+        open[toggled] = !open[toggled];
+        rest = &rest[len..];
+    }
+    if !current.is_empty() {
+        spans.push(Span::styled(current, style(open)));
+    }
+    spans
+}
+
+/// Whether the marker followed by `after` opens a style. It has to be attached to the following
+/// text and be closed later by a marker attached to the preceding text.
+fn opens(after: &str, marker: &str) -> bool {
+    let attached = |c: char| !c.is_whitespace() && c != '*';
+    after.starts_with(attached)
+        && after
+            .match_indices(marker)
+            .any(|(i, _)| after[..i].ends_with(attached))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(lines: &[Spans]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|l| l.0.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_render() {
+        let markdown =
+            "--- Day 1 ---\n------\n\n\n* a **`6000`** [b](/c) *d*\n```ignore\n*x*\n```\n# Part";
+        let lines = render(markdown);
+        assert_eq!(
+            text(&lines),
+            ["--- Day 1 ---", "", "  • a 6000 b d", "  *x*", "Part"]
+        );
+        let strong = &lines[2].0[2];
+        assert_eq!(strong.content, "6000");
+        assert_eq!(strong.style.fg, Some(Color::Yellow));
+        assert!(strong.style.add_modifier.contains(Modifier::BOLD));
+
+        let lines = render("2 * 3 is *six*");
+        assert_eq!(text(&lines), ["2 * 3 is six"]);
+        let [prose, emphasis] = &lines[0].0[..] else {
+            panic!("expected two spans, got {:?}", lines[0]);
+        };
+        assert_eq!(prose.content, "2 * 3 is ");
+        assert!(!prose.style.add_modifier.contains(Modifier::ITALIC));
+        assert!(emphasis.style.add_modifier.contains(Modifier::ITALIC));
+        assert_eq!(text(&render("a*b and c **d")), ["a*b and c **d"]);
+    }
+}
@@ -0,0 +1,882 @@
+mod list;
+mod markdown;
+mod picker;
+
+use crate::answers::{Answers, Verdict};
+use crate::examples::Example;
+use crate::execute::{Execution, Outcome};
+use crate::inputs::Inputs;
+use crate::report::Record;
+use common::profile::SpanTiming;
+use common::{Day, Task, Timings, Year};
+use list::{Filter, block, navigate, page_size, scroll};
+use picker::{Action, Picker};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::stderr;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::time::{Duration, Instant};
+
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use crossterm::{
+    event::{Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen},
+};
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Paragraph, Wrap};
+use tui::{
+    Frame, Terminal,
+    backend::{Backend, CrosstermBackend},
+    widgets::{List, ListItem, ListState},
+};
+
+#[derive(Debug, clap_derive::Parser)]
+pub struct Args {
+    #[clap(flatten)]
+    inputs: crate::inputs::InputArgs,
+    #[clap(
+        short,
+        long,
+        help = "The number of tasks run in parallel. Defaults to the number of CPUs."
+    )]
+    jobs: Option<usize>,
+}
+
+/// Whether both paths refer to the same file, even if one of them is relative.
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Switches the terminal to the alternate screen in raw mode and restores it when dropped, so
+/// the terminal is usable again however the tui is left.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> Result<Self, std::io::Error> {
+        enable_raw_mode()?;
+        execute!(stderr(), EnterAlternateScreen)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(stderr(), LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+    }
+}
+
+/// A row of the task tree.
+#[derive(Debug, Clone, Copy)]
+enum Node {
+    Year(&'static Year),
+    Day(&'static Year, &'static Day),
+    Task(&'static Year, &'static Day, &'static Task),
+}
+
+type Key = (&'static str, &'static str, &'static str);
+
+impl Node {
+    fn year(self) -> &'static Year {
+        match self {
+            Node::Year(y) | Node::Day(y, _) | Node::Task(y, _, _) => y,
+        }
+    }
+
+    /// All tasks below this node including the node itself.
+    fn tasks(self) -> Vec<(&'static Year, &'static Day, &'static Task)> {
+        let days = match self {
+            Node::Year(y) => y.days,
+            Node::Day(_, d) => std::slice::from_ref(d),
+            Node::Task(y, d, t) => return vec![(y, d, t)],
+        };
+        let year = self.year();
+        days.iter()
+            .flat_map(|d| d.tasks.iter().map(move |t| (year, d, t)))
+            .collect()
+    }
+
+    /// The day of the node or the year if it is a year itself.
+    fn day_or_year(self) -> Node {
+        match self {
+            Node::Task(y, d, _) => Node::Day(y, d),
+            node => node,
+        }
+    }
+}
+
+/// The result of the last run of a task.
+struct Finished {
+    record: Record,
+    timings: Timings,
+    spans: Vec<SpanTiming>,
+}
+
+/// A task waiting for or running on a worker thread.
+struct Job {
+    id: usize,
+    year: &'static Year,
+    day: &'static Day,
+    task: &'static Task,
+    input: PathBuf,
+}
+
+impl Job {
+    fn key(&self) -> Key {
+        (self.year.name, self.day.name, self.task.name)
+    }
+}
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+fn spinner(elapsed: Duration) -> char {
+    SPINNER[(elapsed.as_millis() / 100) as usize % SPINNER.len()]
+}
+
+/// The state of the dashboard showing the task tree next to the results.
+struct Dashboard {
+    inputs: Inputs,
+    user: Option<String>,
+    /// Inputs picked for a day instead of the one given by the inputs layout.
+    picked: HashMap<(&'static str, &'static str), PathBuf>,
+    expanded_years: HashSet<&'static str>,
+    expanded_days: HashSet<(&'static str, &'static str)>,
+    /// Shows only the tasks whose full name matches, with all years and days expanded.
+    filter: Filter,
+    rows: Vec<Node>,
+    state: ListState,
+    page: usize,
+    results: HashMap<Key, Finished>,
+    /// The input picker shown instead of the tree while it is open.
+    picker: Option<Picker>,
+    /// Whether the puzzle description of the selected day is shown below the results.
+    show_puzzle: bool,
+    /// Whether the arrow keys scroll the description instead of moving in the tree.
+    puzzle_focus: bool,
+    /// The day whose rendered description is shown, to reset the scroll once another is selected.
+    puzzle_day: Option<(&'static str, &'static str)>,
+    puzzle: Vec<Spans<'static>>,
+    puzzle_scroll: usize,
+    puzzle_page: usize,
+    /// The maximum number of tasks running at once.
+    jobs: usize,
+    next_id: usize,
+    queue: VecDeque<Job>,
+    running: Vec<(Job, Instant)>,
+    send: Sender<(usize, Execution)>,
+    recv: Receiver<(usize, Execution)>,
+}
+
+impl Dashboard {
+    fn new(inputs: Inputs, user: Option<String>, jobs: usize) -> Self {
+        let (send, recv) = channel();
+        let mut dashboard = Self {
+            inputs,
+            user,
+            picked: HashMap::new(),
+            expanded_years: HashSet::new(),
+            expanded_days: HashSet::new(),
+            filter: Filter::default(),
+            rows: Vec::new(),
+            state: ListState::default(),
+            page: 1,
+            results: HashMap::new(),
+            picker: None,
+            show_puzzle: false,
+            puzzle_focus: false,
+            puzzle_day: None,
+            puzzle: Vec::new(),
+            puzzle_scroll: 0,
+            puzzle_page: 1,
+            jobs: jobs.max(1),
+            next_id: 0,
+            queue: VecDeque::new(),
+            running: Vec::new(),
+            send,
+            recv,
+        };
+        dashboard.update_rows();
+        dashboard.state.select(Some(0));
+        dashboard
+    }
+
+    fn selected(&self) -> Option<Node> {
+        self.rows.get(self.state.selected()?).copied()
+    }
+
+    /// Rebuilds the visible rows after expanding or collapsing a node.
+    fn update_rows(&mut self) {
+        self.rows.clear();
+        let filter = &self.filter;
+        let matches = |y: &Year, d: &Day, t: &Task| {
+            filter.matches(&format!("{}::{}::{}", y.name, d.name, t.name))
+        };
+        for year in crate::YEARS {
+            let days = year
+                .days
+                .iter()
+                .filter(|d| d.tasks.iter().any(|t| matches(year, d, t)))
+                .collect::<Vec<_>>();
+            if days.is_empty() {
+                continue;
+            }
+            self.rows.push(Node::Year(year));
+            if filter.is_empty() && !self.expanded_years.contains(year.name) {
+                continue;
+            }
+            for day in days {
+                self.rows.push(Node::Day(year, day));
+                if filter.is_empty() && !self.expanded_days.contains(&(year.name, day.name)) {
+                    continue;
+                }
+                let tasks = day.tasks.iter().filter(|t| matches(year, day, t));
+                self.rows
+                    .extend(tasks.map(|task| Node::Task(year, day, task)));
+            }
+        }
+    }
+
+    /// Rebuilds the rows after the filter changed keeping the selection if it is still visible.
+    fn refresh(&mut self) {
+        let selected = self.selected();
+        self.update_rows();
+        if let Some(node) = selected {
+            self.select(node);
+        }
+        if self.state.selected().is_none() && !self.rows.is_empty() {
+            self.state.select(Some(0));
+        }
+    }
+
+    fn select(&mut self, node: Node) {
+        let index = self.rows.iter().position(|row| match (row, node) {
+            (Node::Year(a), Node::Year(b)) => std::ptr::eq(*a, b),
+            (Node::Day(_, a), Node::Day(_, b)) => std::ptr::eq(*a, b),
+            (Node::Task(_, _, a), Node::Task(_, _, b)) => std::ptr::eq(*a, b),
+            _ => false,
+        });
+        self.state.select(index);
+    }
+
+    /// Expands or collapses the selected year or day.
+    fn set_expanded(&mut self, expanded: bool) {
+        let Some(node) = self.selected() else {
+            return;
+        };
+        let changed = match node {
+            Node::Year(y) if expanded => self.expanded_years.insert(y.name),
+            Node::Year(y) => self.expanded_years.remove(y.name),
+            Node::Day(y, d) if expanded => self.expanded_days.insert((y.name, d.name)),
+            Node::Day(y, d) => self.expanded_days.remove(&(y.name, d.name)),
+            Node::Task(..) => false,
+        };
+        if changed {
+            self.update_rows();
+            self.select(node);
+        }
+    }
+
+    fn is_expanded(&self, node: Node) -> bool {
+        match node {
+            Node::Year(y) => self.expanded_years.contains(y.name),
+            Node::Day(y, d) => self.expanded_days.contains(&(y.name, d.name)),
+            Node::Task(..) => false,
+        }
+    }
+
+    /// Collapses the selected node or moves to its parent if it is not expanded.
+    fn collapse(&mut self) {
+        match self.selected() {
+            Some(node) if self.filter.is_empty() && self.is_expanded(node) => {
+                self.set_expanded(false)
+            }
+            Some(Node::Day(y, _)) => self.select(Node::Year(y)),
+            Some(Node::Task(y, d, _)) => self.select(Node::Day(y, d)),
+            _ => {}
+        }
+    }
+
+    fn input(&self, year: &'static Year, day: &'static Day) -> PathBuf {
+        match self.picked.get(&(year.name, day.name)) {
+            Some(path) => path.clone(),
+            None => self.layout_input(year, day),
+        }
+    }
+
+    /// The input of the day given by the inputs layout.
+    fn layout_input(&self, year: &'static Year, day: &'static Day) -> PathBuf {
+        let path = self.inputs.path(self.user.as_deref(), year, day);
+        path.expect("the user is resolved on startup")
+    }
+
+    /// Queues the tasks unless they are already queued or running. Without an input the one of
+    /// the day is used.
+    fn run(
+        &mut self,
+        tasks: Vec<(&'static Year, &'static Day, &'static Task)>,
+        input: Option<&Path>,
+    ) {
+        for (year, day, task) in tasks {
+            let key = (year.name, day.name, task.name);
+            if self.is_queued(key) || self.elapsed(key).is_some() {
+                continue;
+            }
+            let input = match input {
+                Some(input) => input.to_owned(),
+                None => self.input(year, day),
+            };
+            self.queue.push_back(Job {
+                id: self.next_id,
+                year,
+                day,
+                task,
+                input,
+            });
+            self.next_id += 1;
+        }
+        self.dispatch();
+    }
+
+    /// Starts queued tasks on worker threads while there are free slots.
+    fn dispatch(&mut self) {
+        while self.running.len() < self.jobs {
+            let Some(job) = self.queue.pop_front() else {
+                return;
+            };
+            let (id, task, input) = (job.id, job.task, job.input.clone());
+            let send = self.send.clone();
+            let spawned = std::thread::Builder::new()
+                .name(format!("{}::{}", task.module, task.name))
+                .spawn(move || {
+                    let execution = match std::fs::read(&input) {
+                        Ok(bytes) => crate::execute::run(task, bytes, None),
+                        Err(e) => Outcome::Err(e.to_string()).into(),
+                    };
+                    let _ = send.send((id, execution));
+                });
+            match spawned {
+                Ok(_) => self.running.push((job, Instant::now())),
+                Err(e) => self.finish(job, Outcome::Err(e.to_string()).into()),
+            }
+        }
+    }
+
+    /// Collects the results of finished tasks. Results of cancelled tasks are dropped.
+    fn receive(&mut self) {
+        while let Ok((id, execution)) = self.recv.try_recv() {
+            let Some(index) = self.running.iter().position(|(job, _)| job.id == id) else {
+                continue;
+            };
+            let (job, _) = self.running.remove(index);
+            self.finish(job, execution);
+        }
+        self.dispatch();
+    }
+
+    /// Removes the tasks from the queue and abandons them if they are already running. Their
+    /// threads keep running in the background but free their slot for the next task.
+    fn cancel(&mut self, tasks: Vec<(&'static Year, &'static Day, &'static Task)>) {
+        let keys = tasks
+            .iter()
+            .map(|(y, d, t)| (y.name, d.name, t.name))
+            .collect::<HashSet<_>>();
+        self.queue.retain(|job| !keys.contains(&job.key()));
+        self.running.retain(|(job, _)| !keys.contains(&job.key()));
+        self.dispatch();
+    }
+
+    /// Compares the outcome against the stored answers if the input of the layout was used or
+    /// against the expected answer if the input is an example.
+    fn verdict(&self, job: &Job, outcome: &Outcome) -> Verdict {
+        let layout = self.layout_input(job.year, job.day);
+        if same_file(&job.input, &layout) {
+            return Answers::load(job.year, self.user.as_deref())
+                .map(|a| a.verify(job.day, job.task, outcome))
+                .unwrap_or(Verdict::Unknown);
+        }
+        let examples = Example::list(job.year, job.day);
+        let expected = examples
+            .iter()
+            .find(|example| same_file(&job.input, &example.input))
+            .and_then(|example| example.expected(job.task));
+        match expected {
+            Some(expected) => crate::answers::compare(&expected, outcome),
+            None => Verdict::Unknown,
+        }
+    }
+
+    fn finish(&mut self, job: Job, execution: Execution) {
+        let verdict = self.verdict(&job, &execution.outcome);
+        let key = job.key();
+        let record = Record {
+            year: job.year,
+            day: job.day,
+            task: job.task,
+            user: self.user.clone(),
+            input: job.input,
+            outcome: execution.outcome,
+            duration: execution.timings.total(),
+            memory: execution.memory,
+            verdict,
+        };
+        let finished = Finished {
+            record,
+            timings: execution.timings,
+            spans: execution.spans,
+        };
+        self.results.insert(key, finished);
+    }
+
+    fn is_queued(&self, key: Key) -> bool {
+        self.queue.iter().any(|job| job.key() == key)
+    }
+
+    /// How long the task has been running if it is running.
+    fn elapsed(&self, key: Key) -> Option<Duration> {
+        let (_, started) = self.running.iter().find(|(job, _)| job.key() == key)?;
+        Some(started.elapsed())
+    }
+
+    /// The state of a queued or running task shown instead of its last result.
+    fn progress(&self, key: Key) -> Option<Span<'static>> {
+        let style = Style::default().fg(Color::Yellow);
+        match self.elapsed(key) {
+            Some(elapsed) => Some(Span::styled(
+                format!("{} {}", spinner(elapsed), crate::format_duration(elapsed)),
+                style,
+            )),
+            None if self.is_queued(key) => Some(Span::styled("queued", style)),
+            None => None,
+        }
+    }
+
+    fn style(record: &Record) -> Style {
+        let color = match (&record.outcome, &record.verdict) {
+            (Outcome::Ok(_), Verdict::Pass) => Color::Green,
+            (Outcome::Ok(_), Verdict::Unknown) => Color::Reset,
+            _ => Color::Red,
+        };
+        Style::default().fg(color)
+    }
+
+    fn tree_item(&self, node: Node) -> ListItem<'static> {
+        let marker = match self.is_expanded(node) || !self.filter.is_empty() {
+            true => "▾",
+            false => "▸",
+        };
+        let line = match node {
+            Node::Year(y) => Spans::from(format!("{marker} {}", y.name)),
+            Node::Day(_, d) => Spans::from(format!("  {marker} {}", d.name)),
+            Node::Task(y, d, t) => {
+                let key = (y.name, d.name, t.name);
+                let mut spans = vec![Span::raw(format!("      {:8}", t.name))];
+                if let Some(progress) = self.progress(key) {
+                    spans.push(progress);
+                } else if let Some(finished) = self.results.get(&key) {
+                    let record = &finished.record;
+                    let status = match record.verdict {
+                        Verdict::Unknown => record.outcome.status(),
+                        _ => record.verdict.label(),
+                    };
+                    spans.push(Span::styled(status, Self::style(record)));
+                }
+                Spans::from(spans)
+            }
+        };
+        ListItem::new(line)
+    }
+
+    /// The lines of the results pane for the selected node.
+    fn details(&self, node: Node) -> Vec<Spans<'static>> {
+        let mut lines = Vec::new();
+        if let Node::Task(y, d, t) = node {
+            let key = (y.name, d.name, t.name);
+            let input = self.input(y, d);
+            lines.push(Spans::from(format!("input:   {}", input.display())));
+            if let Some(progress) = self.progress(key) {
+                lines.push(Spans::from(vec![Span::raw("state:   "), progress]));
+            }
+            let Some(finished) = self.results.get(&key) else {
+                lines.push(Spans::from("Not run yet, press 'r' to run it."));
+                return lines;
+            };
+            let record = &finished.record;
+            let timings = finished.timings;
+            let status = format!("{} {}", record.outcome.status(), record.verdict);
+            lines.push(Spans::from(vec![
+                Span::raw("status:  "),
+                Span::styled(status, Self::style(record)),
+            ]));
+            lines.push(Spans::from(format!(
+                "time:    {} (parse {}, solve {})",
+                crate::format_duration(record.duration),
+                crate::format_duration(timings.parse),
+                crate::format_duration(timings.solve),
+            )));
+            if let Some(memory) = record.memory {
+                lines.push(Spans::from(format!("memory:  {memory}")));
+            }
+            lines.push(Spans::from(""));
+            lines.extend(
+                record
+                    .outcome
+                    .message()
+                    .lines()
+                    .map(|l| Spans::from(l.to_owned())),
+            );
+            lines.push(Spans::from(""));
+            lines.push(Spans::from("profile:"));
+            let profile = crate::format_profile(&finished.spans, timings);
+            lines.extend(profile.lines().map(|l| Spans::from(l.to_owned())));
+            return lines;
+        }
+
+        node.tasks()
+            .into_iter()
+            .map(|(y, d, t)| self.summary(y, d, t))
+            .collect()
+    }
+
+    /// A single line with the state or the last result of the task.
+    fn summary(&self, y: &'static Year, d: &'static Day, t: &'static Task) -> Spans<'static> {
+        let key = (y.name, d.name, t.name);
+        if let Some(progress) = self.progress(key) {
+            let name = Span::raw(format!("{}::{}::{} ", y.name, d.name, t.name));
+            return Spans::from(vec![name, progress]);
+        }
+        match self.results.get(&key) {
+            Some(finished) => {
+                let record = &finished.record;
+                Spans::from(Span::styled(record.to_string(), Self::style(record)))
+            }
+            None => Spans::from(format!(
+                "{:3} {:11} {}::{}::{}",
+                "-", "", y.name, d.name, t.name
+            )),
+        }
+    }
+
+    /// Draws the input picker with the results of its tasks below the preview.
+    fn draw_picker<B: Backend>(&mut self, f: &mut Frame<B>, area: Rect) {
+        // taken out while drawing as the results are looked up in the dashboard
+        let Some(mut picker) = self.picker.take() else {
+            return;
+        };
+        let [list, right] = *Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(area)
+        else {
+            unreachable!("the layout has two parts")
+        };
+        let height = picker.tasks.len() as u16 + 2;
+        let [preview, results] = *Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(height)])
+            .split(right)
+        else {
+            unreachable!("the layout has two parts")
+        };
+        picker.draw(f, list, preview);
+
+        // several files may be tried in a row, so the input of each result is shown as well
+        let lines = picker
+            .tasks
+            .iter()
+            .map(|&(y, d, t)| {
+                let mut line = self.summary(y, d, t);
+                if let Some(finished) = self.results.get(&(y.name, d.name, t.name)) {
+                    let input = finished.record.input.file_name().unwrap_or_default();
+                    let input = format!("  ({})", input.to_string_lossy());
+                    line.0
+                        .push(Span::styled(input, Style::default().fg(Color::DarkGray)));
+                }
+                line
+            })
+            .collect::<Vec<_>>();
+        f.render_widget(Paragraph::new(lines).block(block("Results")), results);
+        self.picker = Some(picker);
+    }
+
+    /// Draws the puzzle description of the day of the node.
+    fn draw_puzzle<B: Backend>(&mut self, f: &mut Frame<B>, area: Rect, node: Option<Node>) {
+        let day = match node {
+            Some(Node::Day(y, d) | Node::Task(y, d, _)) => Some((y, d)),
+            _ => None,
+        };
+        let key = day.map(|(y, d)| (y.name, d.name));
+        if self.puzzle_day != key || self.puzzle.is_empty() {
+            self.puzzle_day = key;
+            self.puzzle_scroll = 0;
+            self.puzzle = match day.and_then(|(_, d)| d.doc) {
+                Some(doc) => markdown::render(doc),
+                None => vec![Spans::from(Span::styled(
+                    "No description",
+                    Style::default().fg(Color::Gray),
+                ))],
+            };
+        }
+        self.puzzle_page = page_size(area);
+        let title = match self.puzzle_focus {
+            true => "Puzzle (scrolling)",
+            false => "Puzzle",
+        };
+        let paragraph = Paragraph::new(self.puzzle.clone())
+            .block(block(title))
+            .wrap(Wrap { trim: false })
+            .scroll((self.puzzle_scroll as u16, 0));
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw<B: Backend>(&mut self, f: &mut Frame<B>) {
+        let [main, help] = *Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(1)])
+            .split(f.size())
+        else {
+            unreachable!("the layout has two parts")
+        };
+        let status = format!(
+            "{} running, {} queued",
+            self.running.len(),
+            self.queue.len()
+        );
+        if self.picker.is_some() {
+            self.draw_picker(f, main);
+            let keys = "↑↓ move  enter pick  r run  tab scroll preview  backspace parent  esc close  / filter  q quit";
+            f.render_widget(Paragraph::new(format!("{status} | {keys}")), help);
+            return;
+        }
+
+        let [tree, results] = *Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(30), Constraint::Min(20)])
+            .split(main)
+        else {
+            unreachable!("the layout has two parts")
+        };
+
+        let items = self
+            .rows
+            .iter()
+            .map(|&node| self.tree_item(node))
+            .collect::<Vec<_>>();
+        self.page = page_size(tree);
+        let title = format!("Tasks{}", self.filter.title());
+        let list = List::new(items)
+            .block(block(&title))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, tree, &mut self.state);
+
+        let node = self.selected();
+        let results = match self.show_puzzle {
+            true => {
+                let [results, puzzle] = *Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                    .split(results)
+                else {
+                    unreachable!("the layout has two parts")
+                };
+                self.draw_puzzle(f, puzzle, node);
+                results
+            }
+            false => results,
+        };
+
+        let (title, lines) = match node {
+            Some(node) => {
+                let title = match node {
+                    Node::Year(y) => y.name.to_owned(),
+                    Node::Day(y, d) => format!("{}::{}", y.name, d.name),
+                    Node::Task(y, d, t) => format!("{}::{}::{}", y.name, d.name, t.name),
+                };
+                (title, self.details(node))
+            }
+            None => ("Results".to_owned(), Vec::new()),
+        };
+        let paragraph = Paragraph::new(lines).block(block(&title));
+        f.render_widget(paragraph, results);
+
+        let keys = match self.puzzle_focus {
+            true => "↑↓ scroll  tab/esc back  p hide  q quit",
+            false => {
+                "↑↓ move  ←→ fold  enter open  r/d/y run  c cancel  i input  p puzzle  / filter  q quit"
+            }
+        };
+        f.render_widget(Paragraph::new(format!("{status} | {keys}")), help);
+    }
+}
+
+fn dashboard<B: Backend>(term: &mut Terminal<B>, mut dashboard: Dashboard) -> std::io::Result<()> {
+    loop {
+        dashboard.receive();
+        term.draw(|f| dashboard.draw(f))?;
+
+        // redraw regularly to animate the running tasks
+        if !crossterm::event::poll(Duration::from_millis(100))? {
+            continue;
+        }
+        let key = match crossterm::event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            _ => continue,
+        };
+        if let Some(picker) = &mut dashboard.picker {
+            let (year, day, tasks) = (picker.year, picker.day, picker.tasks.clone());
+            match picker.handle(key.code) {
+                Action::None => {}
+                Action::Pick(input) => {
+                    let layout = dashboard.layout_input(year, day);
+                    match same_file(&input, &layout) {
+                        true => dashboard.picked.remove(&(year.name, day.name)),
+                        false => dashboard.picked.insert((year.name, day.name), input),
+                    };
+                    dashboard.picker = None;
+                }
+                Action::Run(input) => dashboard.run(tasks, Some(&input)),
+                Action::Close => dashboard.picker = None,
+                Action::Quit => return Ok(()),
+            }
+            continue;
+        }
+        if dashboard.puzzle_focus {
+            match key.code {
+                KeyCode::Tab | KeyCode::Esc | KeyCode::Backspace => dashboard.puzzle_focus = false,
+                KeyCode::Char('p') => {
+                    dashboard.show_puzzle = false;
+                    dashboard.puzzle_focus = false;
+                }
+                KeyCode::Char('q') => return Ok(()),
+                code => {
+                    let (offset, len) = (dashboard.puzzle_scroll, dashboard.puzzle.len());
+                    dashboard.puzzle_scroll = scroll(offset, len, dashboard.puzzle_page, code);
+                }
+            }
+            continue;
+        }
+        if dashboard.filter.edit(key.code) {
+            dashboard.refresh();
+            continue;
+        }
+        let (len, page) = (dashboard.rows.len(), dashboard.page);
+        if navigate(&mut dashboard.state, len, page, key.code) {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') => return Ok(()),
+            KeyCode::Char('p') => {
+                dashboard.show_puzzle = !dashboard.show_puzzle;
+                continue;
+            }
+            KeyCode::Tab if dashboard.show_puzzle => {
+                dashboard.puzzle_focus = true;
+                continue;
+            }
+            _ => {}
+        }
+        let Some(node) = dashboard.selected() else {
+            continue;
+        };
+        match key.code {
+            KeyCode::Right => dashboard.set_expanded(true),
+            KeyCode::Left | KeyCode::Esc | KeyCode::Backspace => dashboard.collapse(),
+            KeyCode::Enter => match node {
+                Node::Task(..) => dashboard.run(node.tasks(), None),
+                _ => {
+                    let expanded = dashboard.is_expanded(node);
+                    dashboard.set_expanded(!expanded)
+                }
+            },
+            KeyCode::Char('r') => dashboard.run(node.tasks(), None),
+            KeyCode::Char('d') => dashboard.run(node.day_or_year().tasks(), None),
+            KeyCode::Char('y') => dashboard.run(Node::Year(node.year()).tasks(), None),
+            KeyCode::Char('c') => dashboard.cancel(node.tasks()),
+            KeyCode::Char('i') => {
+                let (Node::Day(y, d) | Node::Task(y, d, _)) = node else {
+                    continue;
+                };
+                let input = dashboard.layout_input(y, d);
+                let dir = dashboard.inputs.dir(dashboard.user.as_deref(), y, d);
+                dashboard.picker = Some(Picker::new(y, d, node.tasks(), input, dir));
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Inputs(#[from] crate::inputs::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+pub fn run(args: Args) -> Result<(), Error> {
+    let inputs = args.inputs.inputs();
+    let user = inputs.user(args.inputs.user())?;
+
+    let _guard = TerminalGuard::enter()?;
+    let backend = CrosstermBackend::new(stderr());
+    let mut terminal = Terminal::new(backend)?;
+    let jobs = args
+        .jobs
+        .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get()))
+        .unwrap_or(1);
+    dashboard(&mut terminal, Dashboard::new(inputs, user, jobs))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree() {
+        let mut dashboard = Dashboard::new(Inputs::default(), None, 1);
+        assert_eq!(dashboard.rows.len(), crate::YEARS.len());
+
+        navigate(&mut dashboard.state, dashboard.rows.len(), 1, KeyCode::Down);
+        dashboard.set_expanded(true);
+        let year = crate::YEARS[1];
+        assert_eq!(dashboard.rows.len(), crate::YEARS.len() + year.days.len());
+        let tasks = year.days.iter().map(|d| d.tasks.len()).sum::<usize>();
+        assert_eq!(dashboard.selected().unwrap().tasks().len(), tasks);
+
+        navigate(&mut dashboard.state, dashboard.rows.len(), 1, KeyCode::Down);
+        dashboard.collapse();
+        assert!(matches!(dashboard.selected(), Some(Node::Year(y)) if y.name == year.name));
+    }
+
+    #[test]
+    fn test_filter() {
+        let mut dashboard = Dashboard::new(Inputs::default(), None, 1);
+        for c in "/2022::DAY16".chars() {
+            dashboard.filter.edit(KeyCode::Char(c));
+        }
+        dashboard.refresh();
+        let rows = dashboard.rows.iter().map(|row| match row {
+            Node::Year(y) => y.name,
+            Node::Day(_, d) => d.name,
+            Node::Task(_, _, t) => t.name,
+        });
+        assert_eq!(
+            rows.collect::<Vec<_>>(),
+            ["aoc_2022", "day16", "task1", "task2"]
+        );
+
+        let mut state = ListState::default();
+        state.select(Some(1));
+        assert!(navigate(&mut state, 4, 2, KeyCode::PageDown));
+        assert_eq!(state.selected(), Some(3));
+        assert!(navigate(&mut state, 4, 2, KeyCode::Down));
+        assert_eq!(state.selected(), Some(0));
+        assert!(!navigate(&mut state, 4, 2, KeyCode::Enter));
+    }
+}
@@ -0,0 +1,278 @@
+use super::list::{self, StatefulList, block, navigate, page_size};
+use crate::examples::Example;
+use common::{Day, Task, Year};
+use crossterm::event::KeyCode;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use tui::layout::Rect;
+use tui::widgets::Paragraph;
+use tui::{Frame, backend::Backend};
+
+/// A file offered by the picker.
+enum Entry {
+    /// The input given by the inputs layout.
+    Input(PathBuf),
+    Example(Example),
+    Parent,
+    Dir(PathBuf),
+    File(PathBuf),
+}
+
+impl Entry {
+    /// The file to run a task on or `None` for directories.
+    fn file(&self) -> Option<&Path> {
+        match self {
+            Entry::Input(path) | Entry::File(path) => Some(path),
+            Entry::Example(example) => Some(&example.input),
+            Entry::Parent | Entry::Dir(_) => None,
+        }
+    }
+}
+
+impl Display for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = |path: &Path| {
+            let name = path.file_name().unwrap_or(path.as_os_str());
+            name.to_string_lossy().into_owned()
+        };
+        match self {
+            Entry::Input(path) => write!(f, "input    {}", path.display()),
+            Entry::Example(example) => write!(f, "example  {}", example.name),
+            Entry::Parent => f.write_str("../"),
+            Entry::Dir(path) => write!(f, "{}/", name(path)),
+            Entry::File(path) => f.write_str(&name(path)),
+        }
+    }
+}
+
+/// The content of the highlighted file.
+struct Preview {
+    path: PathBuf,
+    lines: Vec<String>,
+    /// The length of the longest line in characters.
+    columns: usize,
+    scroll: usize,
+    page: usize,
+}
+
+impl Preview {
+    fn load(path: &Path) -> Self {
+        let lines = match std::fs::read(path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes)
+                .lines()
+                .map(str::to_owned)
+                .collect(),
+            Err(e) => vec![format!("Failed to read the file: {e}")],
+        };
+        let columns = lines.iter().map(|l| l.chars().count()).max();
+        Self {
+            path: path.to_owned(),
+            columns: columns.unwrap_or_default(),
+            lines,
+            scroll: 0,
+            page: 1,
+        }
+    }
+
+    fn scroll(&mut self, code: KeyCode) {
+        self.scroll = list::scroll(self.scroll, self.lines.len(), self.page, code);
+    }
+}
+
+/// What the dashboard should do after a key was handled by the picker.
+pub enum Action {
+    None,
+    /// Use the file as the input of the day.
+    Pick(PathBuf),
+    /// Run the tasks on the file while staying in the picker.
+    Run(PathBuf),
+    Close,
+    Quit,
+}
+
+/// Lists the input and the examples of a day next to the files of a directory and shows a
+/// preview of the highlighted file.
+pub struct Picker {
+    pub year: &'static Year,
+    pub day: &'static Day,
+    /// The tasks run on the highlighted file.
+    pub tasks: Vec<(&'static Year, &'static Day, &'static Task)>,
+    input: PathBuf,
+    dir: PathBuf,
+    list: StatefulList<Entry>,
+    preview: Option<Preview>,
+    /// Whether the arrow keys scroll the preview instead of moving in the list.
+    scrolling: bool,
+}
+
+impl Picker {
+    /// Opens the picker in `dir` with `input`, the input of the day given by the layout,
+    /// highlighted.
+    pub fn new(
+        year: &'static Year,
+        day: &'static Day,
+        tasks: Vec<(&'static Year, &'static Day, &'static Task)>,
+        input: PathBuf,
+        dir: PathBuf,
+    ) -> Self {
+        let dir = match dir.is_dir() {
+            true => dir,
+            false => std::env::current_dir().unwrap_or_default(),
+        };
+        let mut picker = Self {
+            year,
+            day,
+            tasks,
+            input,
+            dir: PathBuf::new(),
+            list: StatefulList::new(String::new(), Vec::new()),
+            preview: None,
+            scrolling: false,
+        };
+        picker.open(dir);
+        picker.list.state.select(Some(0));
+        picker.update_preview();
+        picker
+    }
+
+    /// Lists the entries of the directory below the input and the examples of the day.
+    fn open(&mut self, dir: PathBuf) {
+        // unlike a relative path an absolute one can always be walked up by removing components
+        self.dir = std::fs::canonicalize(&dir).unwrap_or(dir);
+
+        let mut entries = vec![Entry::Input(self.input.clone())];
+        let examples = Example::list(self.year, self.day);
+        entries.extend(examples.into_iter().map(Entry::Example));
+        let fixed = entries.len();
+        if self.dir.parent().is_some() {
+            entries.push(Entry::Parent);
+        }
+        let mut files = std::fs::read_dir(&self.dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .map(|path| match path.is_dir() {
+                true => Entry::Dir(path),
+                false => Entry::File(path),
+            })
+            .collect::<Vec<_>>();
+        files.sort_by_key(|entry| entry.to_string());
+        entries.extend(files);
+
+        let title = format!("Select input in {}", self.dir.display());
+        self.list = StatefulList::new(title, entries);
+        // the directory was opened on purpose, so its first entry is selected
+        let first = self.list.visible.iter().position(|&i| i >= fixed);
+        self.list.state.select(first.or(Some(0)));
+        self.update_preview();
+    }
+
+    fn selected(&self) -> Option<&Entry> {
+        Some(&self.list.items[self.list.selected()?])
+    }
+
+    fn update_preview(&mut self) {
+        let file = self.selected().and_then(Entry::file).map(Path::to_owned);
+        match (file, &self.preview) {
+            (Some(file), Some(preview)) if preview.path == file => {}
+            (Some(file), _) => self.preview = Some(Preview::load(&file)),
+            (None, _) => self.preview = None,
+        }
+    }
+
+    fn parent(&mut self) {
+        if let Some(parent) = self.dir.parent() {
+            self.open(parent.to_owned());
+        }
+    }
+
+    pub fn handle(&mut self, code: KeyCode) -> Action {
+        if self.scrolling {
+            match (code, &mut self.preview) {
+                (KeyCode::Tab | KeyCode::Esc | KeyCode::Backspace, _) => self.scrolling = false,
+                (KeyCode::Char('q'), _) => return Action::Quit,
+                (code, Some(preview)) => preview.scroll(code),
+                (_, None) => {}
+            }
+            return Action::None;
+        }
+
+        if self.list.filter.edit(code) {
+            self.list.update_visible();
+            self.update_preview();
+            return Action::None;
+        }
+        if navigate(
+            &mut self.list.state,
+            self.list.visible.len(),
+            self.list.page,
+            code,
+        ) {
+            self.update_preview();
+            return Action::None;
+        }
+        let file = self.selected().and_then(Entry::file).map(Path::to_owned);
+        match code {
+            KeyCode::Enter => match self.selected() {
+                Some(Entry::Parent) => self.parent(),
+                Some(Entry::Dir(dir)) => self.open(dir.clone()),
+                _ => return file.map_or(Action::None, Action::Pick),
+            },
+            KeyCode::Char('r') => return file.map_or(Action::None, Action::Run),
+            KeyCode::Tab if self.preview.is_some() => self.scrolling = true,
+            KeyCode::Backspace => self.parent(),
+            KeyCode::Esc => return Action::Close,
+            KeyCode::Char('q') => return Action::Quit,
+            _ => {}
+        }
+        Action::None
+    }
+
+    pub fn draw<B: Backend>(&mut self, f: &mut Frame<B>, list: Rect, preview: Rect) {
+        f.render_widget(&mut self.list, list);
+
+        let Some(content) = &mut self.preview else {
+            f.render_widget(Paragraph::new("").block(block("Preview")), preview);
+            return;
+        };
+        content.page = page_size(preview);
+        let title = format!(
+            "{}{}: {} lines, {} columns",
+            match self.scrolling {
+                true => "> ",
+                false => "",
+            },
+            content.path.display(),
+            content.lines.len(),
+            content.columns
+        );
+        let visible = content.lines.iter().skip(content.scroll).take(content.page);
+        let lines = visible.cloned().collect::<Vec<_>>().join("\n");
+        f.render_widget(Paragraph::new(lines).block(block(&title)), preview);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_picker() {
+        let year = crate::YEARS.iter().find(|y| y.name == "aoc_2022").unwrap();
+        let day = year.days.iter().find(|d| d.name == "day17").unwrap();
+        let input = PathBuf::from("aoc_2022/inputs/day17.txt");
+        let dir = PathBuf::from("aoc_2022/inputs");
+        let mut picker = Picker::new(year, day, Vec::new(), input, dir);
+        assert!(matches!(picker.selected(), Some(Entry::Input(_))));
+        assert_eq!(picker.preview.as_ref().unwrap().lines.len(), 1);
+
+        picker.handle(KeyCode::Down);
+        assert!(matches!(picker.selected(), Some(Entry::Example(e)) if e.name == "example"));
+        let action = picker.handle(KeyCode::Char('r'));
+        assert!(matches!(action, Action::Run(path) if path.ends_with("example.txt")));
+
+        picker.handle(KeyCode::Backspace);
+        assert!(picker.dir.ends_with("aoc_2022"));
+        assert!(matches!(picker.selected(), Some(Entry::Parent)));
+    }
+}
@@ -0,0 +1,273 @@
+use crate::answers::{Answers, Verdict};
+use crate::examples::Example;
+use crate::execute::{Execution, Outcome};
+use crate::report::Record;
+use crate::select::{Matcher, PartNotFound, Pattern};
+use common::{Day, Year};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, clap_derive::Parser)]
+pub struct Args {
+    #[clap(
+        value_parser = Matcher::year,
+        help = "The year of the task to be watched. (i.e. aoc_2024)"
+    )]
+    year: Matcher,
+    #[clap(
+        value_parser = Matcher::day,
+        help = "The day of the task to be watched. (i.e. day17)"
+    )]
+    day: Matcher,
+    #[clap(
+        value_parser = Matcher::task,
+        help = "The name of the task to be watched. (i.e. task2)"
+    )]
+    task: Matcher,
+    #[clap(
+        short,
+        long,
+        help = "The path to the input file. If omitted it is resolved from the inputs layout."
+    )]
+    input: Option<PathBuf>,
+    #[clap(
+        short,
+        long,
+        help = "A directory of example inputs the task is additionally run on. Defaults to './YEAR/examples/DAY'."
+    )]
+    examples: Option<PathBuf>,
+    #[clap(
+        long,
+        value_parser = crate::parse_duration,
+        default_value = "500ms",
+        help = "How often the files are checked for changes."
+    )]
+    interval: Duration,
+    #[clap(
+        long,
+        help = "Do not rebuild and restart when the source file of the day changes."
+    )]
+    no_rebuild: bool,
+    #[clap(flatten)]
+    inputs: crate::inputs::InputArgs,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    PartNotFound(#[from] PartNotFound),
+    #[error(transparent)]
+    Inputs(#[from] crate::inputs::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// The modification times of all watched files.
+type Snapshot = Vec<(PathBuf, Option<SystemTime>)>;
+
+fn snapshot(paths: impl IntoIterator<Item = PathBuf>) -> Snapshot {
+    paths
+        .into_iter()
+        .map(|p| {
+            let modified = std::fs::metadata(&p).and_then(|m| m.modified()).ok();
+            (p, modified)
+        })
+        .collect()
+}
+
+/// Prints the lines which differ between the previous and the current answer.
+fn print_diff(previous: &str, current: &str) {
+    let previous = previous.lines().collect::<Vec<_>>();
+    let current = current.lines().collect::<Vec<_>>();
+    for i in 0..previous.len().max(current.len()) {
+        let (p, c) = (previous.get(i), current.get(i));
+        if p == c {
+            continue;
+        }
+        if let Some(p) = p {
+            println!("  - {p}");
+        }
+        if let Some(c) = c {
+            println!("  + {c}");
+        }
+    }
+}
+
+/// The cargo features this binary was built with, which the rebuilt binary gets as well.
+fn features() -> Vec<&'static str> {
+    [
+        ("parallel", cfg!(feature = "parallel")),
+        ("interactive", cfg!(feature = "interactive")),
+        ("memory", cfg!(feature = "memory")),
+        ("profile", cfg!(feature = "profile")),
+    ]
+    .into_iter()
+    .filter_map(|(name, enabled)| enabled.then_some(name))
+    .collect()
+}
+
+/// Rebuilds the binary with cargo and replaces the current process with the rebuilt binary using
+/// the same arguments.
+fn restart() -> Result<(), Error> {
+    // resolved before building as the path of a replaced executable is no longer valid
+    let exe = std::env::current_exe()?;
+    let mut build = Command::new(std::env::var_os("CARGO").unwrap_or("cargo".into()));
+    build.args(["build", "--no-default-features", "--features"]);
+    build.arg(features().join(","));
+    if !cfg!(debug_assertions) {
+        build.arg("--release");
+    }
+    if !build.status()?.success() {
+        println!("Build failed, waiting for further changes...");
+        return Ok(());
+    }
+
+    let mut command = Command::new(exe);
+    command.args(std::env::args_os().skip(1));
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // only returns if the rebuilt binary could not be started
+        Err(command.exec().into())
+    }
+    #[cfg(not(unix))]
+    std::process::exit(command.status()?.code().unwrap_or(1));
+}
+
+pub fn run(args: Args) -> Result<(), Error> {
+    let pattern = Pattern {
+        year: args.year,
+        day: args.day,
+        task: args.task,
+    };
+    let tasks = crate::select::resolve(crate::YEARS, &pattern)?;
+
+    let layout = args.inputs.inputs();
+    let user = match &args.input {
+        Some(_) => None,
+        None => layout.user(args.inputs.user())?,
+    };
+    let mut input_paths = HashMap::new();
+    for (year, day, _) in &tasks {
+        let path = match &args.input {
+            Some(path) => path.clone(),
+            None => layout.path(user.as_deref(), year, day)?,
+        };
+        input_paths.insert((year.name, day.name), path);
+    }
+    let inputs = |year: &Year, day: &Day| input_paths[&(year.name, day.name)].clone();
+    let sources = tasks
+        .iter()
+        .map(|(y, d, _)| PathBuf::from(format!("{}/src/{}.rs", y.name, d.name)))
+        .collect::<Vec<_>>();
+    let source_snapshot = || snapshot(sources.iter().cloned());
+    let examples = |year, day| match &args.examples {
+        Some(dir) => Example::list_dir(dir),
+        None => Example::list(year, day),
+    };
+
+    let mut previous = HashMap::new();
+    let mut last_inputs = Snapshot::new();
+    let mut last_sources = source_snapshot();
+    loop {
+        let current = tasks.iter().flat_map(|(y, d, _)| {
+            let examples = examples(y, d).into_iter().map(|e| e.input);
+            let answers = Answers::path(y, user.as_deref());
+            [inputs(y, d), answers].into_iter().chain(examples)
+        });
+        let current = snapshot(current);
+
+        let current_sources = source_snapshot();
+        if !args.no_rebuild && current_sources != last_sources {
+            println!("\nSource changed, rebuilding...");
+            restart()?;
+            last_sources = current_sources;
+        }
+
+        if current == last_inputs {
+            std::thread::sleep(args.interval);
+            continue;
+        }
+        last_inputs = current;
+
+        // clear the screen and move the cursor to the top left corner
+        print!("\x1b[2J\x1b[H");
+        let now = time_of_day();
+        println!("Watching for changes (last run at {now}), press Ctrl+C to stop.\n");
+
+        let mut answers = HashMap::new();
+        for (year, day, task) in &tasks {
+            // a broken answers file is reported and the watch goes on until it is fixed
+            let answers = answers.entry(year.name).or_insert_with(|| {
+                match Answers::load(year, user.as_deref()) {
+                    Ok(answers) => Some(answers),
+                    Err(e) => {
+                        println!("{e}\n");
+                        None
+                    }
+                }
+            });
+            // the stored answers only apply to the default inputs
+            let default = match (&args.input, answers) {
+                (None, Some(answers)) => answers.expected(day, task).map(str::to_owned),
+                _ => None,
+            };
+            let runs = [(inputs(year, day), default)].into_iter().chain(
+                examples(year, day).into_iter().map(|e| {
+                    let expected = e.expected(task);
+                    (e.input, expected)
+                }),
+            );
+            for (path, expected) in runs {
+                let Execution {
+                    outcome,
+                    timings,
+                    memory,
+                    ..
+                } = match std::fs::read(&path) {
+                    Ok(input) => crate::execute::run(task, input, None),
+                    Err(e) => Outcome::Err(e.to_string()).into(),
+                };
+
+                let verdict = match expected {
+                    Some(expected) => crate::answers::compare(&expected, &outcome),
+                    None => Verdict::Unknown,
+                };
+                let record = Record {
+                    year,
+                    day,
+                    task,
+                    user: user.clone(),
+                    input: path,
+                    outcome,
+                    duration: timings.total(),
+                    memory,
+                    verdict,
+                };
+                println!("{record}");
+                println!("  input: {:?}", record.input);
+
+                let key = (year.name, day.name, task.name, record.input.clone());
+                let message = record.outcome.message();
+                match previous.insert(key, message.clone()) {
+                    Some(prev) if prev != message => print_diff(&prev, &message),
+                    Some(_) => println!("  (unchanged)"),
+                    None => {}
+                }
+                println!();
+            }
+        }
+    }
+}
+
+/// The current time of day (UTC) formatted as `HH:MM:SS`.
+fn time_of_day() -> String {
+    let secs = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (h, m, s) = ((secs / 3600) % 24, (secs / 60) % 60, secs % 60);
+    format!("{h:02}:{m:02}:{s:02} UTC")
+}
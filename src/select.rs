@@ -0,0 +1,480 @@
+use common::{Day, Task, Year};
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PartNotFound {
+    #[error("Failed to find year {0}{1}")]
+    Year(String, Suggestion),
+    #[error("Failed to find day {0}{1}")]
+    Day(String, Suggestion),
+    #[error("Failed to find task {0}{1}")]
+    Task(String, Suggestion),
+    #[error("Failed to find a task matching both the patterns and the filter {0}")]
+    Filter(String),
+}
+
+/// The name of the registry closest to a name which was not found, if any is close enough.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Suggestion(Option<String>);
+
+impl Suggestion {
+    fn new<'a>(matcher: &Matcher, names: impl IntoIterator<Item = &'a str>) -> Self {
+        // ranges and globs are not misspelled names
+        let Matcher::Glob(name) = matcher else {
+            return Self::default();
+        };
+        if name.contains(['*', '?']) {
+            return Self::default();
+        }
+        let closest = names
+            .into_iter()
+            .map(|n| (edit_distance(name, n), n))
+            .filter(|&(distance, n)| distance <= 2.max(n.len() / 3))
+            .min();
+        Self(closest.map(|(_, n)| n.to_owned()))
+    }
+}
+
+impl Display for Suggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(name) => write!(f, ", did you mean '{name}'?"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The number of single character insertions, deletions and substitutions to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + (ca != cb) as usize;
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid pattern '{0}', expected 'YEAR[::DAY[::TASK]]' or 'YEAR[/DAY[/TASK]]'")]
+pub struct InvalidPattern(String);
+
+pub type Selection = (&'static Year, &'static Day, &'static Task);
+
+/// A component of a task selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    Year,
+    Day,
+    Task,
+}
+
+impl Part {
+    /// All names of this part in the registry sorted by name.
+    pub fn names(self) -> Vec<&'static str> {
+        let years = crate::YEARS.iter();
+        let mut names = match self {
+            Part::Year => years.map(|y| y.name).collect::<Vec<_>>(),
+            Part::Day => years.flat_map(|y| y.days.iter().map(|d| d.name)).collect(),
+            Part::Task => years
+                .flat_map(|y| y.days.iter())
+                .flat_map(|d| d.tasks.iter().map(|t| t.name))
+                .collect(),
+        };
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Resolves a short alias to its name in the registry. Years may be given by their number
+    /// (i.e. `2024` or `24`), days by their number (i.e. `6`) and tasks by their number or a
+    /// letter (i.e. `2` or `b`).
+    pub fn alias(self, s: &str) -> Option<&'static str> {
+        let number = match (self, s.as_bytes()) {
+            (Part::Task, &[c]) if c.is_ascii_lowercase() => (c - b'a' + 1) as u32,
+            _ => s.parse::<u32>().ok()?,
+        };
+        let short_year = self == Part::Year && s.len() <= 2;
+        self.names().into_iter().find(|name| {
+            let digits = name.trim_start_matches(|c: char| !c.is_ascii_digit());
+            match digits.parse::<u32>() {
+                Ok(n) if short_year => n % 100 == number,
+                Ok(n) => n == number,
+                Err(_) => false,
+            }
+        })
+    }
+}
+
+/// Matches a single name of the registry.
+///
+/// Either a glob (i.e. `day1*`) or an inclusive range of names (i.e. `day05..day10`). Ranges may
+/// omit either bound and compare names lexicographically which works due to the zero padded
+/// day numbers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Matcher {
+    Glob(String),
+    Range(Option<String>, Option<String>),
+}
+
+impl Default for Matcher {
+    fn default() -> Self {
+        Self::Glob("*".to_owned())
+    }
+}
+
+impl Matcher {
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            Matcher::Glob(pattern) => glob_match(pattern, name),
+            Matcher::Range(start, end) => {
+                start.as_deref().is_none_or(|s| s <= name)
+                    && end.as_deref().is_none_or(|e| name <= e)
+            }
+        }
+    }
+
+    /// Parses a matcher of the given part replacing short aliases with the names of the registry.
+    pub fn parse(s: &str, part: Part) -> Result<Self, InvalidPattern> {
+        let alias = |name: String| match part.alias(&name) {
+            Some(alias) => alias.to_owned(),
+            None => name,
+        };
+        let matcher = match s.parse()? {
+            Matcher::Glob(glob) => Matcher::Glob(alias(glob)),
+            Matcher::Range(start, end) => Matcher::Range(start.map(alias), end.map(alias)),
+        };
+        Ok(matcher)
+    }
+
+    pub fn year(s: &str) -> Result<Self, InvalidPattern> {
+        Self::parse(s, Part::Year)
+    }
+
+    pub fn day(s: &str) -> Result<Self, InvalidPattern> {
+        Self::parse(s, Part::Day)
+    }
+
+    pub fn task(s: &str) -> Result<Self, InvalidPattern> {
+        Self::parse(s, Part::Task)
+    }
+
+    /// Matches a year either by its full name or without the crate prefix (i.e. `2022`).
+    fn matches_year(&self, name: &str) -> bool {
+        self.matches(name) || name.strip_prefix("aoc_").is_some_and(|n| self.matches(n))
+    }
+}
+
+impl FromStr for Matcher {
+    type Err = InvalidPattern;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((start, end)) = s.split_once("..") else {
+            return match s {
+                "" => Ok(Self::default()),
+                _ => Ok(Self::Glob(s.to_owned())),
+            };
+        };
+        let end = end.strip_prefix('=').unwrap_or(end);
+        if start.contains(['*', '?']) || end.contains(['*', '?']) {
+            return Err(InvalidPattern(s.to_owned()));
+        }
+        let bound = |b: &str| (!b.is_empty()).then(|| b.to_owned());
+        Ok(Self::Range(bound(start), bound(end)))
+    }
+}
+
+impl Display for Matcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Matcher::Glob(pattern) => f.write_str(pattern),
+            Matcher::Range(start, end) => {
+                let start = start.as_deref().unwrap_or_default();
+                let end = end.as_deref().unwrap_or_default();
+                write!(f, "{start}..{end}")
+            }
+        }
+    }
+}
+
+/// Selects tasks of the registry by year, day and task. Written as `YEAR::DAY::TASK` where
+/// omitted trailing parts match everything. The parts may also be separated by `/` or `.` (i.e.
+/// `2024/6/2` or `24.6.b`) and use the aliases of [`Part::alias`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pattern {
+    pub year: Matcher,
+    pub day: Matcher,
+    pub task: Matcher,
+}
+
+impl Pattern {
+    /// Splits a pattern into the parts it is written with, of which there may be more than three.
+    pub fn parts(s: &str) -> Vec<&str> {
+        // a single `.` only separates parts if it is not part of a range
+        let separator = match s {
+            _ if s.contains("::") => "::",
+            _ if s.contains('/') => "/",
+            _ if s.contains('.') && !s.contains("..") => ".",
+            _ => "::",
+        };
+        s.split(separator).collect()
+    }
+
+    pub fn matches(&self, year: &Year, day: &Day, task: &Task) -> bool {
+        self.matches_names(year.name, day.name, task.name)
+    }
+
+    pub fn matches_names(&self, year: &str, day: &str, task: &str) -> bool {
+        self.year.matches_year(year) && self.day.matches(day) && self.task.matches(task)
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = InvalidPattern;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = Self::parts(s);
+        if parts.len() > 3 {
+            return Err(InvalidPattern(s.to_owned()));
+        }
+        let mut parts = parts.into_iter();
+        let mut next = |part| Matcher::parse(parts.next().unwrap_or_default(), part);
+        Ok(Self {
+            year: next(Part::Year)?,
+            day: next(Part::Day)?,
+            task: next(Part::Task)?,
+        })
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}::{}::{}", self.year, self.day, self.task)
+    }
+}
+
+/// Matches `name` against `pattern` where `*` matches any sequence of characters and `?` matches
+/// exactly one character.
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+
+    let (mut p, mut n) = (0, 0);
+    // position of the last `*` in the pattern and the name position it was tried at
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, pos)) => {
+                    backtrack = Some((star, pos + 1));
+                    p = star + 1;
+                    n = pos + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Resolves the pattern against `years` returning all matching tasks in registry order.
+pub fn resolve(
+    all_years: &[&'static Year],
+    pattern: &Pattern,
+) -> Result<Vec<Selection>, PartNotFound> {
+    let years = all_years
+        .iter()
+        .copied()
+        .filter(|y| pattern.year.matches_year(y.name))
+        .collect::<Vec<_>>();
+    if years.is_empty() {
+        let names = all_years
+            .iter()
+            .flat_map(|y| [y.name, y.name.trim_start_matches("aoc_")]);
+        let suggestion = Suggestion::new(&pattern.year, names);
+        return Err(PartNotFound::Year(pattern.year.to_string(), suggestion));
+    }
+
+    let all_days = years
+        .into_iter()
+        .flat_map(|y| y.days.iter().map(move |d| (y, d)))
+        .collect::<Vec<_>>();
+    let days = all_days
+        .iter()
+        .filter(|(_, d)| pattern.day.matches(d.name))
+        .collect::<Vec<_>>();
+    if days.is_empty() {
+        let names = all_days.iter().map(|(_, d)| d.name);
+        let suggestion = Suggestion::new(&pattern.day, names);
+        return Err(PartNotFound::Day(pattern.day.to_string(), suggestion));
+    }
+
+    let all_tasks = days
+        .into_iter()
+        .flat_map(|&(y, d)| d.tasks.iter().map(move |t| (y, d, t)))
+        .collect::<Vec<_>>();
+    let tasks = all_tasks
+        .iter()
+        .copied()
+        .filter(|(_, _, t)| pattern.task.matches(t.name))
+        .collect::<Vec<_>>();
+    if tasks.is_empty() {
+        let names = all_tasks.iter().map(|(_, _, t)| t.name);
+        let suggestion = Suggestion::new(&pattern.task, names);
+        return Err(PartNotFound::Task(pattern.task.to_string(), suggestion));
+    }
+    Ok(tasks)
+}
+
+/// Resolves all patterns against `years` returning the tasks matched by any of them in registry
+/// order. Every pattern has to match at least one task.
+pub fn resolve_all(
+    years: &[&'static Year],
+    patterns: &[Pattern],
+) -> Result<Vec<Selection>, PartNotFound> {
+    for pattern in patterns {
+        resolve(years, pattern)?;
+    }
+    let tasks = years
+        .iter()
+        .flat_map(|y| y.days.iter().map(move |d| (*y, d)))
+        .flat_map(|(y, d)| d.tasks.iter().map(move |t| (y, d, t)))
+        .filter(|(y, d, t)| patterns.iter().any(|p| p.matches(y, d, t)))
+        .collect();
+    Ok(tasks)
+}
+
+/// Resolves the patterns like [`resolve_all`] and keeps the tasks also matching `filter`. Fails
+/// if the filter excludes every selected task even though each of its parts exists on its own.
+pub fn resolve_filtered(
+    years: &[&'static Year],
+    patterns: &[Pattern],
+    filter: &Pattern,
+) -> Result<Vec<Selection>, PartNotFound> {
+    let mut tasks = resolve_all(years, patterns)?;
+    resolve(years, filter)?;
+    tasks.retain(|(y, d, t)| filter.matches(y, d, t));
+    if tasks.is_empty() {
+        return Err(PartNotFound::Filter(filter.to_string()));
+    }
+    Ok(tasks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("day01", "day01"));
+        assert!(!glob_match("day01", "day010"));
+        assert!(glob_match("day1*", "day17"));
+        assert!(glob_match("day1*", "day1"));
+        assert!(!glob_match("day1*", "day21"));
+        assert!(glob_match("*", "aoc_2022"));
+        assert!(glob_match("*_2022", "aoc_2022"));
+        assert!(glob_match("d*y?5", "day05"));
+        assert!(!glob_match("d*y?5", "day5"));
+        assert!(glob_match("*a*a*", "banana"));
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        let pattern = "2022::day05..day10".parse::<Pattern>().unwrap();
+        assert_eq!(pattern.year, Matcher::Glob("aoc_2022".to_owned()));
+        assert_eq!(
+            pattern.day,
+            Matcher::Range(Some("day05".to_owned()), Some("day10".to_owned()))
+        );
+        assert_eq!(pattern.task, Matcher::default());
+
+        assert!("a::b::c::d".parse::<Pattern>().is_err());
+        assert!("day1*..day2".parse::<Matcher>().is_err());
+    }
+
+    #[test]
+    fn test_aliases() {
+        let expected = Pattern {
+            year: Matcher::Glob("aoc_2024".to_owned()),
+            day: Matcher::Glob("day06".to_owned()),
+            task: Matcher::Glob("task2".to_owned()),
+        };
+        for alias in ["2024/6/2", "24.6.b", "2024::06::task2", "aoc_2024/day06/b"] {
+            assert_eq!(alias.parse::<Pattern>().unwrap(), expected, "{alias}");
+        }
+
+        let range = Matcher::day("5..10").unwrap();
+        assert_eq!(
+            range,
+            Matcher::Range(Some("day05".to_owned()), Some("day10".to_owned()))
+        );
+        assert_eq!(Matcher::day("1*").unwrap(), Matcher::Glob("1*".to_owned()));
+        assert_eq!(Part::Day.alias("26"), None);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let pattern = "aoc_2022::day1?::task2".parse().unwrap();
+        let tasks = resolve(crate::YEARS, &pattern).unwrap();
+        assert_eq!(tasks.len(), 10);
+        assert!(
+            tasks
+                .iter()
+                .all(|(y, _, t)| y.name == "aoc_2022" && t.name == "task2")
+        );
+
+        let pattern = "2022::day05..day10::task1".parse().unwrap();
+        assert_eq!(resolve(crate::YEARS, &pattern).unwrap().len(), 6);
+
+        let pattern = "aoc_2022::day26".parse().unwrap();
+        let err = resolve(crate::YEARS, &pattern).unwrap_err();
+        assert!(matches!(err, PartNotFound::Day(_, _)));
+
+        let pattern = "aoc_2022::dya05".parse().unwrap();
+        let err = resolve(crate::YEARS, &pattern).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to find day dya05, did you mean 'day05'?"
+        );
+        let pattern = "2O22".parse().unwrap();
+        let err = resolve(crate::YEARS, &pattern).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to find year 2O22, did you mean '2022'?"
+        );
+    }
+
+    #[test]
+    fn test_resolve_filtered() {
+        let patterns = ["2022::day0?".parse().unwrap()];
+        let filter = "*::day05..day30::task2".parse().unwrap();
+        let tasks = resolve_filtered(crate::YEARS, &patterns, &filter).unwrap();
+        assert_eq!(tasks.len(), 5);
+
+        let filter = "2021::day25..day30".parse().unwrap();
+        let err = resolve_filtered(crate::YEARS, &patterns, &filter).unwrap_err();
+        assert!(matches!(err, PartNotFound::Filter(_)));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("task1", "task1"), 0);
+        assert_eq!(edit_distance("tsak1", "task1"), 2);
+        assert_eq!(edit_distance("day5", "day05"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+}
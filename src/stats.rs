@@ -0,0 +1,79 @@
+use common::Timings;
+use std::fmt::Display;
+use std::time::Duration;
+
+/// Summary statistics over the durations of repeated runs.
+pub struct Stats {
+    min: Duration,
+    median: Duration,
+    mean: Duration,
+    stddev: Duration,
+}
+
+impl Stats {
+    /// Panics if there are no samples.
+    pub fn new(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let mid = samples.len() / 2;
+        let n = samples.len() as f64;
+        let mean = samples.iter().map(Duration::as_secs_f64).sum::<f64>() / n;
+        let variance = samples
+            .iter()
+            .map(|d| (d.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        Self {
+            min: samples[0],
+            median: match samples.len() % 2 {
+                0 => (samples[mid - 1] + samples[mid]) / 2,
+                _ => samples[mid],
+            },
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+
+    pub fn median(&self) -> Duration {
+        self.median
+    }
+
+    /// Formats the statistics of the parse, solve and total time of the runs as a table.
+    pub fn table(samples: &[Timings]) -> String {
+        let parse = Self::new(samples.iter().map(|t| t.parse).collect());
+        let solve = Self::new(samples.iter().map(|t| t.solve).collect());
+        let total = Self::new(samples.iter().map(Timings::total).collect());
+        let header = format!(
+            "       {:>10} {:>10} {:>10} {:>10}",
+            "min", "median", "mean", "stddev"
+        );
+        format!("{header}\nparse  {parse}\nsolve  {solve}\ntotal  {total}")
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let min = crate::format_duration(self.min);
+        let median = crate::format_duration(self.median);
+        let mean = crate::format_duration(self.mean);
+        let stddev = crate::format_duration(self.stddev);
+        write!(f, "{min:>10} {median:>10} {mean:>10} {stddev:>10}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_median() {
+        let ms = |ms: &[u64]| ms.iter().map(|&ms| Duration::from_millis(ms)).collect();
+        assert_eq!(
+            Stats::new(ms(&[3, 1, 2])).median(),
+            Duration::from_millis(2)
+        );
+        assert_eq!(
+            Stats::new(ms(&[4, 1, 2, 3])).median(),
+            Duration::from_micros(2500)
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use crate::answers::Verdict;
+use crate::execute::Outcome;
+use crate::report::Record;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::time::Duration;
+
+/// Aggregated statistics over the records of a run.
+pub struct Summary<'a> {
+    records: &'a [(Record, bool)],
+    wall_time: Duration,
+    top: usize,
+    budget: Option<Duration>,
+}
+
+impl<'a> Summary<'a> {
+    /// Creates a summary over `records` where the flag marks records whose input was missing.
+    pub fn new(
+        records: &'a [(Record, bool)],
+        wall_time: Duration,
+        top: usize,
+        budget: Option<Duration>,
+    ) -> Self {
+        Self {
+            records,
+            wall_time,
+            top,
+            budget,
+        }
+    }
+
+    /// The time spent in all tasks summed up across threads.
+    pub fn cpu_time(&self) -> Duration {
+        self.records.iter().map(|(r, _)| r.duration).sum()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.records
+            .iter()
+            .filter(|(r, _)| r.verdict.is_fail())
+            .count()
+    }
+
+    fn name(record: &Record) -> String {
+        format!(
+            "{}::{}::{}",
+            record.year.name, record.day.name, record.task.name
+        )
+    }
+}
+
+impl Display for Summary<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fmt = crate::format_duration;
+
+        let (mut ok, mut err, mut panic, mut timeout, mut missing) = (0, 0, 0, 0, 0);
+        let (mut pass, mut fail, mut unknown) = (0, 0, 0);
+        for (record, missing_input) in self.records {
+            match (&record.outcome, missing_input) {
+                (_, true) => missing += 1,
+                (Outcome::Ok(_), _) => ok += 1,
+                (Outcome::Err(_), _) => err += 1,
+                (Outcome::Panic { .. }, _) => panic += 1,
+                (Outcome::Timeout(_), _) => timeout += 1,
+            }
+            match record.verdict {
+                Verdict::Pass => pass += 1,
+                Verdict::Fail { .. } => fail += 1,
+                Verdict::Unknown => unknown += 1,
+            }
+        }
+
+        writeln!(f, "Finished!")?;
+        writeln!(f, "wall time: {}", fmt(self.wall_time))?;
+        writeln!(
+            f,
+            "cpu time:  {} (summed over all tasks)",
+            fmt(self.cpu_time())
+        )?;
+        writeln!(
+            f,
+            "tasks:     {ok} OK, {err} ERR, {panic} PANIC, {timeout} TIMEOUT, {missing} missing input"
+        )?;
+        writeln!(f, "verdicts:  {pass} PASS, {fail} FAIL, {unknown} UNKNOWN")?;
+
+        let mut by_duration = self.records.iter().map(|(r, _)| r).collect::<Vec<_>>();
+        by_duration.sort_by_key(|r| std::cmp::Reverse(r.duration));
+        if self.top > 0 {
+            writeln!(f, "\nslowest tasks:")?;
+            for record in by_duration.iter().take(self.top) {
+                writeln!(f, "  {:>10}  {}", fmt(record.duration), Self::name(record))?;
+            }
+        }
+
+        let mut years = BTreeMap::<&str, (usize, usize, Duration)>::new();
+        for (record, _) in self.records {
+            let (count, ok, time) = years.entry(record.year.name).or_default();
+            *count += 1;
+            *ok += matches!(record.outcome, Outcome::Ok(_)) as usize;
+            *time += record.duration;
+        }
+        writeln!(f, "\nper year:")?;
+        for (year, (count, ok, time)) in years {
+            writeln!(f, "  {year:8}  {ok:3}/{count:<3} OK  {:>10}", fmt(time))?;
+        }
+
+        let mut users = BTreeMap::<&str, (usize, usize, usize)>::new();
+        for (record, _) in self.records {
+            let Some(user) = &record.user else {
+                continue;
+            };
+            let (count, pass, fail) = users.entry(user).or_default();
+            *count += 1;
+            *pass += matches!(record.verdict, Verdict::Pass) as usize;
+            *fail += record.verdict.is_fail() as usize;
+        }
+        if !users.is_empty() {
+            writeln!(f, "\nper user:")?;
+            for (user, (count, pass, fail)) in users {
+                writeln!(
+                    f,
+                    "  {user:10}  {count:3} tasks  {pass:3} PASS  {fail:3} FAIL"
+                )?;
+            }
+        }
+
+        if let Some(budget) = self.budget {
+            let over = by_duration
+                .iter()
+                .take_while(|r| r.duration > budget)
+                .collect::<Vec<_>>();
+            writeln!(f, "\nbudget of {}:", fmt(budget))?;
+            match over.is_empty() {
+                true => writeln!(f, "  all tasks are within the budget")?,
+                false => {
+                    for record in over {
+                        writeln!(f, "  {:>10}  {}", fmt(record.duration), Self::name(record))?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}